@@ -1,6 +1,6 @@
 use cosmwasm_schema::write_api;
 
-use lighthouse_v2::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use lighthouse::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 fn main() {
     write_api! {
@@ -43,9 +43,9 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps<EvmQueryWrapper>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps<EvmQueryWrapper>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let tract = Lighthouse::default();
-    tract.query(deps, msg)
+    tract.query(deps, env, msg)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
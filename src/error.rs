@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use cw_utils::ParseReplyError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +7,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -77,4 +81,97 @@ pub enum ContractError {
 
     #[error("Invalid Token Id")]
     InvalidTokenId {},
+
+    #[error("Not On Allowlist")]
+    NotOnAllowlist {},
+
+    #[error("Invalid Voucher")]
+    InvalidVoucher {},
+
+    #[error("Voucher Expired")]
+    VoucherExpired {},
+
+    #[error("Voucher Already Used")]
+    VoucherAlreadyUsed {},
+
+    #[error("Invalid EVM Signature")]
+    InvalidEvmSignature {},
+
+    #[error("Wallet Not Delegated")]
+    NotDelegated {},
+
+    #[error("Random Seed Not Revealed")]
+    RandomSeedNotRevealed {},
+
+    #[error("Invalid Random Seed")]
+    InvalidRandomSeed {},
+
+    #[error("Collection Paused")]
+    CollectionPaused {},
+
+    #[error("Emergency Stop")]
+    EmergencyStop {},
+
+    #[error("Collection Cancelled")]
+    CollectionCancelled {},
+
+    #[error("Invalid Escrow Status")]
+    InvalidEscrowStatus {},
+
+    #[error("Nothing To Claim")]
+    NothingToClaim {},
+
+    #[error("Escrow, vesting and return window cannot be combined")]
+    ConflictingProceedsOptions {},
+
+    #[error("Token Not Returnable")]
+    NotReturnable {},
+
+    #[error("Raffle Not Open")]
+    RaffleNotOpen {},
+
+    #[error("Raffle Already Entered")]
+    RaffleAlreadyEntered {},
+
+    #[error("Raffle Already Drawn")]
+    RaffleAlreadyDrawn {},
+
+    #[error("Raffle Not Drawn")]
+    RaffleNotDrawn {},
+
+    #[error("Not Raffle Winner")]
+    NotRaffleWinner {},
+
+    #[error("Raffle Draw Expired")]
+    RaffleDrawExpired {},
+
+    #[error("Auction Not Active")]
+    AuctionNotActive {},
+
+    #[error("Auction Not Ended")]
+    AuctionNotEnded {},
+
+    #[error("Auction Has Bids")]
+    AuctionHasBids {},
+
+    #[error("Bid Too Low")]
+    BidTooLow {},
+
+    #[error("Presale Not Open")]
+    PresaleNotOpen {},
+
+    #[error("Open Edition Not Ended")]
+    OpenEditionNotEnded {},
+
+    #[error("Not Supported By Stock cw721")]
+    UnsupportedByStockCw721 {},
+
+    #[error("Code Not Approved")]
+    CodeNotApproved {},
+
+    #[error("Unexpected Collection Address")]
+    UnexpectedCollectionAddress {},
+
+    #[error("Unknown Reply Id")]
+    UnknownReplyId {},
 }
@@ -1,44 +1,101 @@
 use cosmwasm_std::{
     coins,
+    instantiate2_address,
     to_json_binary,
+    Addr,
     Attribute,
+    Binary,
     BankMsg,
     CosmosMsg,
     DepsMut,
     Env,
     MessageInfo,
+    Order,
     ReplyOn,
     Response,
     StdError,
+    StdResult,
+    Storage,
     SubMsg,
     Uint128,
     WasmMsg,
 };
-use data_encoding::BASE64;
+use data_encoding::{ BASE64, HEXLOWER };
 use sha3::{ Digest, Keccak256 };
-use cw2981_royalties::{ ExecuteMsg as Cw2981ExecuteMsg, Metadata as Cw2981Metadata };
+use cw2981_royalties::{
+    ExecuteMsg as Cw2981ExecuteMsg,
+    InstantiateMsg as Cw721BaseInstantiateMsg,
+    Metadata as Cw2981Metadata,
+};
+use cw_ownable::Ownership;
+use cw_storage_plus::Bound;
 
 use crate::{
     helpers::{
+        close_open_edition,
         convert_bech32_to_hex,
+        create_code_registry_key,
+        create_delegation_key,
         create_global_mint_info_key,
         create_group_key,
         create_mint_log_key,
         create_mint_log_key_404,
+        create_payout_msg,
+        create_raffle_key,
+        create_random_pool_key,
+        create_voucher_hash,
+        create_voucher_key,
+        exceeds_supply,
+        normalize_address,
         pad_address_to_bytes32,
+        raffle_draw_deadline,
+        recover_evm_address,
+        validate_404_supply,
         validate_groups,
         validate_merkle_proof,
         validate_payments,
+        validate_vesting,
+        validate_voucher_signer,
+        vested_amount,
     },
     logo::Logo,
     msg::{
         AddPartner,
+        AdminMint,
+        AddToAllowlist,
+        ApproveCode,
+        CancelAuction,
+        CancelCollection,
+        Claim,
+        ClaimProceeds,
+        ClaimRaffleRefund,
+        ClaimRefund,
+        CloseOpenEdition,
+        CreateAuction,
+        DrawRaffle,
+        DelegateWallet,
+        EnterRaffle,
+        EvmAddressProof,
         EvmQueryWrapper,
         ExecuteMsg,
+        FinalizeCollection,
         InstantiateMsg,
         Mint,
+        PauseCollection,
+        PlaceBid,
+        ReleaseReturnFunds,
+        Reserve,
         RegisterCollection,
+        RemoveFromAllowlist,
+        RemovePendingRegistration,
+        ResumeCollection,
         RevealCollectionMetadata,
+        ReturnToken,
+        RevealRandomSeed,
+        RevokeCode,
+        RevokeDelegation,
+        SetEmergencyStop,
+        SettleAuction,
         UnfreezeCollection,
         UpdateAdmin,
         UpdateCollection,
@@ -47,22 +104,63 @@ use crate::{
         UpdateNftContractCwOwnableOwner,
     },
     querier::EvmQuerier,
-    state::{ Collection, Config, Lighthouse, MintInfo, Partner, PaymentType },
+    state::{
+        AllowlistEntry,
+        ApprovedCode,
+        Auction,
+        AuctionStatus,
+        Bid,
+        Collection,
+        Config,
+        Cw721Flavor,
+        Delegation,
+        EscrowStatus,
+        HeldPayment,
+        Milestone,
+        Lighthouse,
+        OpenEdition,
+        MintGroup,
+        MintInfo,
+        Partner,
+        Payment,
+        PaymentType,
+        RaffleState,
+        RaffleTicket,
+        Reservation,
+        StockCw721Info,
+        TokenPayment,
+        VestingBalance,
+    },
     structs::{
         Cw20ExecuteMsg,
+        Cw721MinterResponse,
+        Cw721NumTokensResponse,
+        Cw721OwnerOfResponse,
+        Cw721QueryMsg,
+        Cw721ReceiveMsg,
         Cw2981InstantiateMsg,
         Cw2981LHExecuteExtension,
         Cw2981LHExecuteMsg,
         Cw404ExecuteMsg,
         Cw404InstantiateMsg,
+        Cw1155ExecuteMsg,
+        Cw1155InstantiateMsg,
         EvmMsg,
         FrozenData,
         InstantiateMarketingInfo,
+        Minter,
         MinterResponse,
     },
     ContractError,
 };
 
+/// Group name used to log admin mints, which happen outside of mint groups
+pub const ADMIN_MINT_GROUP: &str = "admin_mint";
+
+/// Raffle winners drawn per DrawRaffle call
+const DEFAULT_DRAW_LIMIT: u32 = 100;
+const MAX_DRAW_LIMIT: u32 = 500;
+
 impl<'a> Lighthouse<'a> {
     pub fn instantiate(
         &self,
@@ -77,6 +175,7 @@ impl<'a> Lighthouse<'a> {
                 fee: msg.fee,
                 registeration_open: msg.registeration_open,
                 next_reply_id: 0,
+                emergency_stop: Some(false),
             })
         )?;
 
@@ -90,6 +189,24 @@ impl<'a> Lighthouse<'a> {
         info: MessageInfo,
         msg: ExecuteMsg
     ) -> Result<Response<EvmMsg>, ContractError> {
+        // During an emergency stop only the stop itself, delegation revokes and the paths
+        // that pay out what holders and creators are owed are allowed
+        let exempt = matches!(
+            msg,
+            ExecuteMsg::SetEmergencyStop(_) |
+                ExecuteMsg::RevokeDelegation(_) |
+                ExecuteMsg::ClaimRefund(_) |
+                ExecuteMsg::ClaimRaffleRefund(_) |
+                ExecuteMsg::ReceiveNft(_) |
+                ExecuteMsg::ReturnToken(_) |
+                ExecuteMsg::ReleaseReturnFunds(_) |
+                ExecuteMsg::Claim(_) |
+                ExecuteMsg::ClaimProceeds(_)
+        );
+        if !exempt && self.config.load(deps.storage)?.emergency_stop == Some(true) {
+            return Err(ContractError::EmergencyStop {});
+        }
+
         match msg {
             ExecuteMsg::UpdateConfig(params) => self.update_config(deps, info, env, params),
             ExecuteMsg::RegisterCollection(params) =>
@@ -106,6 +223,43 @@ impl<'a> Lighthouse<'a> {
                 self.update_nft_contract_cw_ownable_owner(deps, env, info, params),
             ExecuteMsg::UpdateNftContractAdmin(params) =>
                 self.update_nft_contract_admin(deps, env, info, params),
+            ExecuteMsg::AddToAllowlist(params) => self.add_to_allowlist(deps, env, info, params),
+            ExecuteMsg::RemoveFromAllowlist(params) =>
+                self.remove_from_allowlist(deps, env, info, params),
+            ExecuteMsg::DelegateWallet(params) => self.delegate_wallet(deps, env, info, params),
+            ExecuteMsg::RevokeDelegation(params) => self.revoke_delegation(deps, env, info, params),
+            ExecuteMsg::AdminMint(params) => self.admin_mint(deps, env, info, params),
+            ExecuteMsg::RevealRandomSeed(params) =>
+                self.reveal_random_seed(deps, env, info, params),
+            ExecuteMsg::PauseCollection(params) => self.pause_collection(deps, env, info, params),
+            ExecuteMsg::ResumeCollection(params) => self.resume_collection(deps, env, info, params),
+            ExecuteMsg::SetEmergencyStop(params) =>
+                self.set_emergency_stop(deps, env, info, params),
+            ExecuteMsg::FinalizeCollection(params) =>
+                self.finalize_collection(deps, env, info, params),
+            ExecuteMsg::CancelCollection(params) => self.cancel_collection(deps, env, info, params),
+            ExecuteMsg::ClaimRefund(params) => self.claim_refund(deps, env, info, params),
+            ExecuteMsg::ClaimProceeds(params) => self.claim_proceeds(deps, env, info, params),
+            ExecuteMsg::ReceiveNft(params) => self.receive_nft(deps, env, info, params),
+            ExecuteMsg::ReturnToken(params) => self.return_token(deps, env, info, params),
+            ExecuteMsg::ReleaseReturnFunds(params) =>
+                self.release_return_funds(deps, env, info, params),
+            ExecuteMsg::EnterRaffle(params) => self.enter_raffle(deps, env, info, params),
+            ExecuteMsg::DrawRaffle(params) => self.draw_raffle(deps, env, info, params),
+            ExecuteMsg::ClaimRaffleRefund(params) =>
+                self.claim_raffle_refund(deps, env, info, params),
+            ExecuteMsg::CreateAuction(params) => self.create_auction(deps, env, info, params),
+            ExecuteMsg::PlaceBid(params) => self.place_bid(deps, env, info, params),
+            ExecuteMsg::SettleAuction(params) => self.settle_auction(deps, env, info, params),
+            ExecuteMsg::CancelAuction(params) => self.cancel_auction(deps, env, info, params),
+            ExecuteMsg::Reserve(params) => self.reserve(deps, env, info, params),
+            ExecuteMsg::Claim(params) => self.claim(deps, env, info, params),
+            ExecuteMsg::CloseOpenEdition(params) =>
+                self.close_open_edition(deps, env, info, params),
+            ExecuteMsg::ApproveCode(params) => self.approve_code(deps, env, info, params),
+            ExecuteMsg::RevokeCode(params) => self.revoke_code(deps, env, info, params),
+            ExecuteMsg::RemovePendingRegistration(params) =>
+                self.remove_pending_registration(deps, env, info, params),
         }
     }
 
@@ -129,6 +283,32 @@ impl<'a> Lighthouse<'a> {
         Ok(Response::new().add_attribute("action", "update_config"))
     }
 
+    fn set_emergency_stop(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: SetEmergencyStop
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let mut config = self.config.load(deps.storage)?;
+
+        if config.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        config.emergency_stop = Some(msg.stopped);
+        self.config.save(deps.storage, &config)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "set_emergency_stop")
+                .add_attribute("stopped", msg.stopped.to_string())
+                .add_attribute("reason", msg.reason)
+                .add_attribute("sender", info.sender)
+                .add_attribute("time", env.block.time.seconds().to_string())
+        )
+    }
+
     fn register_collection(
         &self,
         deps: DepsMut<EvmQueryWrapper>,
@@ -138,26 +318,34 @@ impl<'a> Lighthouse<'a> {
     ) -> Result<Response<EvmMsg>, ContractError> {
         let mut config = self.config.load(deps.storage)?;
 
-        if config.registeration_open == false {
+        if !config.registeration_open {
             return Err(ContractError::RegisterationClose {});
         }
 
-        if msg.chain == "v1" && msg.collection_type == "721" {
-            if msg.cw721_code.is_none() {
+        if msg.chain == "v2" && msg.collection_type == "721" {
+            if msg.erc721_address.is_none() {
                 return Err(
-                    ContractError::Std(StdError::generic_err("cw721_code is required for v1"))
+                    ContractError::Std(StdError::generic_err("erc721_address is required for v2"))
                 );
             }
-        } else if msg.chain == "v2" && msg.collection_type == "721" {
-            if msg.erc721_address.is_none() {
+        } else if msg.chain == "v1" && msg.collection_type == "1155" {
+            if msg.frozen || msg.hidden_metadata {
                 return Err(
-                    ContractError::Std(StdError::generic_err("erc721_address is required for v2"))
+                    ContractError::Std(
+                        StdError::generic_err("cw1155 collections cannot be frozen or hidden")
+                    )
                 );
             }
-        } else if msg.chain == "v1" && msg.collection_type == "404" {
-            if msg.cw404_code.is_none() {
+        } else if msg.chain == "v2" && msg.collection_type == "1155" {
+            if msg.erc1155_address.is_none() {
+                return Err(
+                    ContractError::Std(StdError::generic_err("erc1155_address is required for v2"))
+                );
+            }
+        } else if msg.chain == "v2" && msg.collection_type == "404" {
+            if msg.erc404_address.is_none() {
                 return Err(
-                    ContractError::Std(StdError::generic_err("cw404_code is required for v1"))
+                    ContractError::Std(StdError::generic_err("erc404_address is required for v2"))
                 );
             }
             if msg.start_order.is_some() {
@@ -165,13 +353,92 @@ impl<'a> Lighthouse<'a> {
                     ContractError::Std(StdError::generic_err("start_order must be None for 404"))
                 );
             }
-        } else {
+            validate_404_supply(msg.supply, &msg.cw404_info, &msg.mint_groups)?;
+        } else if msg.chain == "v1" && msg.collection_type == "404" {
+            if msg.start_order.is_some() {
+                return Err(
+                    ContractError::Std(StdError::generic_err("start_order must be None for 404"))
+                );
+            }
+        } else if msg.chain != "v1" || msg.collection_type != "721" {
             return Err(ContractError::InvalidChain {});
         }
 
+        // v1 contracts are instantiated from an approved code, the default one if omitted
+        let code_id = if msg.chain == "v1" && msg.cw721_address.is_none() {
+            let code_id = match msg.collection_type.as_str() {
+                "721" => msg.cw721_code,
+                "404" => msg.cw404_code,
+                _ => msg.cw1155_code,
+            };
+            let key = create_code_registry_key(&msg.collection_type, &msg.cw721_flavor);
+            Some(self.resolve_code_id(deps.storage, &key, code_id)?)
+        } else {
+            None
+        };
+
         // Validate payments and groups
         validate_payments(&deps, &msg.mint_groups)?;
         validate_groups(&msg.collection_type, &msg.mint_groups)?;
+        validate_voucher_signer(&msg.voucher_signer)?;
+        validate_vesting(&msg.vesting)?;
+
+        let proceeds_options = [
+            msg.escrow == Some(true),
+            msg.vesting.is_some(),
+            msg.return_window.is_some(),
+        ];
+        if proceeds_options.iter().filter(|&&o| o).count() > 1 {
+            return Err(ContractError::ConflictingProceedsOptions {});
+        }
+
+        if let Some(return_window) = &msg.return_window {
+            if msg.chain != "v1" || msg.collection_type != "721" {
+                return Err(
+                    ContractError::Std(StdError::generic_err("Return window is only for v1 721"))
+                );
+            }
+            if return_window.refund_percent > 100 {
+                return Err(ContractError::InvalidFeePercent {});
+            }
+        }
+
+        if msg.random_seed_commitment.is_some() && msg.collection_type != "721" {
+            return Err(
+                ContractError::Std(StdError::generic_err("Random token ids are only for 721"))
+            );
+        }
+
+        // Stock cw721 contracts have no freeze or hidden metadata
+        let stock_cw721 = matches!(
+            msg.cw721_flavor,
+            Some(Cw721Flavor::Base) | Some(Cw721Flavor::Cw2981)
+        );
+        if stock_cw721 {
+            if msg.chain != "v1" || msg.collection_type != "721" {
+                return Err(
+                    ContractError::Std(StdError::generic_err("cw721 flavors are only for v1 721"))
+                );
+            }
+            if msg.frozen || msg.hidden_metadata || msg.placeholder_token_uri.is_some() {
+                return Err(ContractError::UnsupportedByStockCw721 {});
+            }
+        }
+
+        if let Some(end_time) = msg.open_edition_end {
+            if msg.collection_type != "721" || msg.random_seed_commitment.is_some() {
+                return Err(
+                    ContractError::Std(
+                        StdError::generic_err("Open editions are only for sequential 721")
+                    )
+                );
+            }
+            if end_time <= env.block.time.seconds() {
+                return Err(
+                    ContractError::Std(StdError::generic_err("Open edition end must be in future"))
+                );
+            }
+        }
 
         let mut collection = Collection {
             admin: info.sender.clone(),
@@ -185,70 +452,175 @@ impl<'a> Lighthouse<'a> {
             mint_groups: msg.mint_groups.clone(),
             start_order: msg.start_order,
             frozen: msg.frozen,
-            unfreeze_time: msg.unfreeze_time.clone(),
+            unfreeze_time: msg.unfreeze_time,
             hidden_metadata: msg.hidden_metadata,
             placeholder_token_uri: msg.placeholder_token_uri.clone(),
             partner: msg.partner.clone(),
             cw404_info: msg.cw404_info.clone(),
+            voucher_signer: msg.voucher_signer.clone(),
+            random_seed_commitment: msg.random_seed_commitment.clone(),
+            random_seed: None,
+            provenance_hash: msg.provenance_hash.clone(),
+            reveal_time: msg.reveal_time,
+            paused_at: None,
+            paused_by: None,
+            escrow: if msg.escrow == Some(true) { Some(EscrowStatus::Active) } else { None },
+            vesting: msg.vesting.clone(),
+            reached_milestones: None,
+            return_window: msg.return_window.clone(),
+            open_edition: msg.open_edition_end.map(|end_time| OpenEdition {
+                end_time,
+                closed: false,
+            }),
+            cw721_flavor: msg.cw721_flavor.clone(),
+            stock_cw721: if stock_cw721 {
+                Some(StockCw721Info {
+                    base_uri: msg.token_uri.clone(),
+                    uri_suffix: msg.uri_suffix.clone(),
+                    fixed_uri: msg.fixed_uri,
+                    royalty_percentage: msg.royalty_percent,
+                    royalty_payment_address: msg.royalty_wallet.clone(),
+                })
+            } else {
+                None
+            },
         };
 
-        if msg.partner.is_some() {
-            let partner = msg.partner.unwrap();
+        if let Some(partner) = msg.partner {
             if !self.partners.has(deps.storage, partner.to_string()) {
                 return Err(ContractError::PartnerNotFound {});
             }
         }
 
-        if msg.chain == "v1" && msg.collection_type == "721" {
-            self.instantiates.save(deps.storage, config.next_reply_id.clone(), &collection)?;
+        // Instantiate2 salt, for a collection address known before the instantiation
+        let salt = if msg.predictable_address == Some(true) {
+            let seed = format!("{}|{}", info.sender, msg.symbol);
+            Some(Binary::from(Keccak256::digest(seed.as_bytes()).to_vec()))
+        } else {
+            None
+        };
 
-            let sub_msg: Vec<SubMsg<EvmMsg>> = vec![SubMsg {
-                msg: (WasmMsg::Instantiate {
-                    code_id: msg.cw721_code.unwrap(),
-                    msg: to_json_binary(
-                        &(Cw2981InstantiateMsg {
-                            name: msg.name.clone(),
-                            symbol: msg.symbol.clone(),
-                            minter: env.contract.address.to_string(),
-                            is_immutable: msg.is_immutable,
-                            frozen: Some(msg.frozen),
-                            unfreeze_time: msg.unfreeze_time,
-                            hidden_metadata: Some(msg.hidden_metadata),
-                            placeholder_token_uri: msg.placeholder_token_uri,
-                            base_uri: Some(msg.token_uri.clone()),
-                            fixed_uri: msg.fixed_uri,
-                            base_uri_suffix: msg.uri_suffix.clone(),
-                            royalty_percentage: msg.royalty_percent,
-                            royalty_payment_address: msg.royalty_wallet.clone(),
-                        })
-                    )?,
-                    funds: vec![],
-                    admin: Some(info.sender.to_string()),
-                    label: String::from("Instantiate CW721"),
-                }).into(),
-                id: config.next_reply_id.clone(),
-                gas_limit: None,
-                reply_on: ReplyOn::Success,
-            }];
-
-            config.next_reply_id += 1;
-            self.config.save(deps.storage, &config)?;
-            Ok(Response::<EvmMsg>::new().add_submessages(sub_msg))
-        } else if msg.chain == "v1" && msg.collection_type == "404" {
-            self.instantiates.save(deps.storage, config.next_reply_id.clone(), &collection)?;
+        if msg.chain == "v1" && msg.collection_type == "721" && msg.cw721_address.is_some() {
+            let address = deps.api.addr_validate(&msg.cw721_address.clone().unwrap())?.to_string();
+
+            if self.collections.has(deps.storage, address.clone()) {
+                return Err(ContractError::CollectionExists {});
+            }
+
+            // Existing tokens would collide with random ids
+            if msg.random_seed_commitment.is_some() {
+                return Err(
+                    ContractError::Std(
+                        StdError::generic_err("Random token ids are not available for imports")
+                    )
+                );
+            }
+
+            // Lighthouse must be able to mint, as the minter or the cw-ownable owner
+            let minter: Cw721MinterResponse = deps.querier.query_wasm_smart(
+                address.clone(),
+                &(Cw721QueryMsg::Minter {})
+            )?;
+            let ownership: Option<Ownership<Addr>> = deps.querier
+                .query_wasm_smart(address.clone(), &(Cw721QueryMsg::Ownership {}))
+                .ok();
+            let owner = ownership.and_then(|o| o.owner).map(|o| o.to_string());
+
+            let lighthouse = env.contract.address.to_string();
+            if minter.minter.as_ref() != Some(&lighthouse) && owner.as_ref() != Some(&lighthouse) {
+                return Err(ContractError::NotMinter {});
+            }
+
+            // The sender must control the contract, as its wasm admin or owner
+            let contract_info = deps.querier.query_wasm_contract_info(address.clone())?;
+            let sender = info.sender.to_string();
+            if contract_info.admin.as_ref() != Some(&sender) && owner.as_ref() != Some(&sender) {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            // Imported contracts must run an approved code, like instantiated ones
+            let key = create_code_registry_key(&msg.collection_type, &msg.cw721_flavor);
+            self.resolve_code_id(deps.storage, &key, Some(contract_info.code_id))?;
+
+            // Continue from the tokens already minted
+            let num_tokens: Cw721NumTokensResponse = deps.querier.query_wasm_smart(
+                address.clone(),
+                &(Cw721QueryMsg::NumTokens {})
+            )?;
+            let start_order = msg.start_order.unwrap_or(Uint128::zero());
+            let minted_end = start_order + Uint128::from(num_tokens.count);
+            collection.next_token = msg.import_next_token.unwrap_or(minted_end);
+            if collection.next_token < minted_end {
+                return Err(ContractError::InvalidTokenId {});
+            }
 
+            collection.collection_address = Some(address.clone());
+            self.collections.save(deps.storage, address.clone(), &collection)?;
+
+            Ok(
+                Response::new()
+                    .add_attribute("register_collection", "success")
+                    .add_attribute("collection", address)
+                    .add_attribute("type", collection.collection_type)
+                    .add_attribute("chain", msg.chain)
+                    .add_attribute("imported", "true")
+                    .add_attribute("next_token", collection.next_token.to_string())
+            )
+        } else if msg.chain == "v1" && msg.collection_type == "721" && stock_cw721 {
+            // cw721-base and cw2981-royalties share the same instantiate message
+            let instantiate = WasmMsg::Instantiate {
+                code_id: code_id.unwrap(),
+                msg: to_json_binary(
+                    &(Cw721BaseInstantiateMsg {
+                        name: msg.name.clone(),
+                        symbol: msg.symbol.clone(),
+                        minter: env.contract.address.to_string(),
+                    })
+                )?,
+                funds: vec![],
+                admin: Some(info.sender.to_string()),
+                label: String::from("Instantiate CW721"),
+            };
+
+            self.submit_instantiate(deps, &env, &mut config, collection, instantiate, salt)
+        } else if msg.chain == "v1" && msg.collection_type == "721" {
+            let instantiate = WasmMsg::Instantiate {
+                code_id: code_id.unwrap(),
+                msg: to_json_binary(
+                    &(Cw2981InstantiateMsg {
+                        name: msg.name.clone(),
+                        symbol: msg.symbol.clone(),
+                        minter: env.contract.address.to_string(),
+                        is_immutable: msg.is_immutable,
+                        frozen: Some(msg.frozen),
+                        unfreeze_time: msg.unfreeze_time,
+                        hidden_metadata: Some(msg.hidden_metadata),
+                        placeholder_token_uri: msg.placeholder_token_uri,
+                        base_uri: Some(msg.token_uri.clone()),
+                        fixed_uri: msg.fixed_uri,
+                        base_uri_suffix: msg.uri_suffix.clone(),
+                        royalty_percentage: msg.royalty_percent,
+                        royalty_payment_address: msg.royalty_wallet.clone(),
+                    })
+                )?,
+                funds: vec![],
+                admin: Some(info.sender.to_string()),
+                label: String::from("Instantiate CW721"),
+            };
+
+            self.submit_instantiate(deps, &env, &mut config, collection, instantiate, salt)
+        } else if msg.chain == "v1" && msg.collection_type == "404" {
             let cw404_info = msg.cw404_info.unwrap();
 
             let marketing_data: Option<InstantiateMarketingInfo>;
-            let logo: Option<Logo>;
-            if
+            let logo: Option<Logo> = if
                 cw404_info.marketing.is_some() &&
                 cw404_info.marketing.clone().unwrap().logo.is_some()
             {
-                logo = Some(Logo::Url(cw404_info.marketing.clone().unwrap().logo.unwrap()));
+                Some(Logo::Url(cw404_info.marketing.clone().unwrap().logo.unwrap()))
             } else {
-                logo = None;
-            }
+                None
+            };
 
             if let Some(marketing) = cw404_info.marketing.clone() {
                 marketing_data = Some(InstantiateMarketingInfo {
@@ -261,48 +633,61 @@ impl<'a> Lighthouse<'a> {
                 marketing_data = None;
             }
 
-            let sub_msg: Vec<SubMsg<EvmMsg>> = vec![SubMsg {
-                msg: (WasmMsg::Instantiate {
-                    code_id: msg.cw404_code.unwrap(),
-                    msg: to_json_binary(
-                        &(Cw404InstantiateMsg {
-                            name: msg.name.clone(),
-                            symbol: msg.symbol.clone(),
-                            decimals: cw404_info.decimals,
-                            is_immutable: msg.is_immutable,
-                            base_uri: msg.token_uri.clone(),
-                            base_uri_suffix: msg.uri_suffix.clone(),
-                            max_edition: cw404_info.max_edition,
-                            tokens_per_nft: cw404_info.tokens_per_nft,
-                            mint: Some(MinterResponse {
-                                minter: env.contract.address.to_string(),
-                                cap: Some(cw404_info.max_supply),
-                            }),
-                            frozen_data: Some(FrozenData {
-                                frozen: msg.frozen,
-                                unfreeze_time: msg.unfreeze_time,
-                                whitelisted: msg.frozen_whitelist.clone().unwrap_or(Vec::new()),
-                            }),
-                            admin: Some(info.sender.to_string()),
-                            royalty_percentage: msg.royalty_percent,
-                            royalty_payment_address: msg.royalty_wallet.clone(),
-                            marketing: marketing_data,
-                        })
-                    )?,
-                    funds: vec![],
-                    admin: Some(info.sender.to_string()),
-                    label: String::from("Instantiate CW404"),
-                }).into(),
-                id: config.next_reply_id.clone(),
-                gas_limit: None,
-                reply_on: ReplyOn::Success,
-            }];
-
-            config.next_reply_id += 1;
-            self.config.save(deps.storage, &config)?;
-            Ok(Response::<EvmMsg>::new().add_submessages(sub_msg))
+            let instantiate = WasmMsg::Instantiate {
+                code_id: code_id.unwrap(),
+                msg: to_json_binary(
+                    &(Cw404InstantiateMsg {
+                        name: msg.name.clone(),
+                        symbol: msg.symbol.clone(),
+                        decimals: cw404_info.decimals,
+                        is_immutable: msg.is_immutable,
+                        base_uri: msg.token_uri.clone(),
+                        base_uri_suffix: msg.uri_suffix.clone(),
+                        max_edition: cw404_info.max_edition,
+                        tokens_per_nft: cw404_info.tokens_per_nft,
+                        mint: Some(MinterResponse {
+                            minter: env.contract.address.to_string(),
+                            cap: Some(cw404_info.max_supply),
+                        }),
+                        frozen_data: Some(FrozenData {
+                            frozen: msg.frozen,
+                            unfreeze_time: msg.unfreeze_time,
+                            whitelisted: msg.frozen_whitelist.clone().unwrap_or_default(),
+                        }),
+                        admin: Some(info.sender.to_string()),
+                        royalty_percentage: msg.royalty_percent,
+                        royalty_payment_address: msg.royalty_wallet.clone(),
+                        marketing: marketing_data,
+                    })
+                )?,
+                funds: vec![],
+                admin: Some(info.sender.to_string()),
+                label: String::from("Instantiate CW404"),
+            };
+
+            self.submit_instantiate(deps, &env, &mut config, collection, instantiate, salt)
+        } else if msg.chain == "v1" && msg.collection_type == "1155" {
+            let instantiate = WasmMsg::Instantiate {
+                code_id: code_id.unwrap(),
+                msg: to_json_binary(
+                    &(Cw1155InstantiateMsg {
+                        minter: env.contract.address.to_string(),
+                    })
+                )?,
+                funds: vec![],
+                admin: Some(info.sender.to_string()),
+                label: String::from("Instantiate CW1155"),
+            };
+
+            self.submit_instantiate(deps, &env, &mut config, collection, instantiate, salt)
         } else if msg.chain == "v2" {
-            if self.collections.has(deps.storage, msg.erc721_address.clone().unwrap()) {
+            let evm_address = match msg.collection_type.as_str() {
+                "1155" => msg.erc1155_address.clone().unwrap(),
+                "404" => msg.erc404_address.clone().unwrap(),
+                _ => msg.erc721_address.clone().unwrap(),
+            };
+
+            if self.collections.has(deps.storage, evm_address.clone()) {
                 return Err(ContractError::CollectionExists {});
             }
 
@@ -318,13 +703,13 @@ impl<'a> Lighthouse<'a> {
             let has_admin_role = querier.query_has_role(
                 env.contract.address.to_string(),
                 user_evm_address.clone(),
-                msg.erc721_address.clone().unwrap(),
+                evm_address.clone(),
                 vec![0u8; 32] //DEFAULT_ADMIN_ROLE
             )?;
             let has_minter_role = querier.query_has_role(
                 env.contract.address.to_string(),
                 convert_bech32_to_hex(env.contract.address.to_string().as_str())?,
-                msg.erc721_address.clone().unwrap(),
+                evm_address.clone(),
                 Keccak256::digest(b"MINTER_ROLE").to_vec()
             )?;
 
@@ -336,13 +721,13 @@ impl<'a> Lighthouse<'a> {
                 return Err(ContractError::NotMinter {});
             }
 
-            collection.collection_address = msg.erc721_address.clone();
-            self.collections.save(deps.storage, msg.erc721_address.clone().unwrap(), &collection)?;
+            collection.collection_address = Some(evm_address.clone());
+            self.collections.save(deps.storage, evm_address.clone(), &collection)?;
 
             Ok(
                 Response::new()
                     .add_attribute("register_collection", "success")
-                    .add_attribute("collection", msg.erc721_address.unwrap())
+                    .add_attribute("collection", evm_address)
                     .add_attribute("type", collection.collection_type)
                     .add_attribute("chain", msg.chain)
                 /*.add_attribute("user_evm_address", user_evm_address)
@@ -350,14 +735,14 @@ impl<'a> Lighthouse<'a> {
                     .add_attribute("has_minter_role", has_minter_role.to_string())*/
             )
         } else {
-            return Err(ContractError::InvalidChain {});
+            Err(ContractError::InvalidChain {})
         }
     }
 
     pub fn update_collection(
         &self,
         deps: DepsMut<EvmQueryWrapper>,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         msg: UpdateCollection
     ) -> Result<Response<EvmMsg>, ContractError> {
@@ -367,21 +752,87 @@ impl<'a> Lighthouse<'a> {
             return Err(ContractError::Unauthorized {});
         }
 
-        if msg.supply < collection.next_token - collection.start_order.unwrap_or(Uint128::zero()) {
+        // Open editions have no supply while open, and the minted count once closed
+        if close_open_edition(&mut collection, env.block.time.seconds()) {
+            self.reach_milestone(&mut collection, Milestone::SoldOut);
+        }
+        if collection.open_edition.is_some() && msg.supply != collection.supply {
+            return Err(
+                ContractError::Std(StdError::generic_err("Supply is fixed for open editions"))
+            );
+        }
+
+        if
+            collection.open_edition.is_none() &&
+            msg.supply < collection.next_token - collection.start_order.unwrap_or(Uint128::zero())
+        {
             return Err(ContractError::SupplyLowerThanMinted {});
         }
 
+        // The random id pool is sized from the supply, so it is fixed once random mints started
+        if
+            collection.random_seed.is_some() &&
+            msg.supply != collection.supply &&
+            collection.next_token != collection.start_order.unwrap_or(Uint128::zero())
+        {
+            return Err(
+                ContractError::Std(StdError::generic_err("Supply is fixed for random token ids"))
+            );
+        }
+
         // Validate payments and groups
         validate_payments(&deps, &msg.mint_groups)?;
         validate_groups(&collection.collection_type, &msg.mint_groups)?;
+        validate_voucher_signer(&msg.voucher_signer)?;
+
+        let start_order_before = collection.start_order;
+
+        if collection.chain == "v2" && collection.collection_type == "404" {
+            validate_404_supply(msg.supply, &collection.cw404_info, &msg.mint_groups)?;
+        }
+
+        if let Some(start_order) = msg.start_order {
+            if start_order == collection.next_token {
+                collection.next_token = start_order;
+                collection.start_order = msg.start_order;
+            }
+        }
 
-        if msg.start_order.is_some() && msg.start_order.unwrap() == collection.next_token {
-            collection.next_token = msg.start_order.unwrap();
-            collection.start_order = msg.start_order;
+        // One of one auctions must stay outside of the mint range
+        let start = collection.start_order.unwrap_or_default();
+        if
+            (msg.supply != collection.supply || collection.start_order != start_order_before) &&
+            collection.open_edition.is_none() &&
+            self.covers_auctioned_id(deps.storage, &msg.collection, start, start + msg.supply)?
+        {
+            return Err(
+                ContractError::Std(
+                    StdError::generic_err("Mint range cannot cover an auctioned token id")
+                )
+            );
         }
 
         collection.supply = msg.supply;
         collection.mint_groups = msg.mint_groups;
+        collection.voucher_signer = msg.voucher_signer;
+
+        // Stock contracts keep no uri data, it is applied by Lighthouse at mint
+        if collection.is_stock_cw721() {
+            if msg.placeholder_token_uri.is_some() {
+                return Err(ContractError::UnsupportedByStockCw721 {});
+            }
+
+            collection.stock_cw721 = Some(StockCw721Info {
+                base_uri: msg.token_uri.clone(),
+                uri_suffix: msg.uri_suffix.clone(),
+                fixed_uri: msg.fixed_uri,
+                royalty_percentage: msg.royalty_percent,
+                royalty_payment_address: msg.royalty_wallet,
+            });
+            self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
+            return Ok(Response::default());
+        }
 
         if collection.chain == "v1" && collection.collection_type == "721" {
             let execute: CosmosMsg<EvmMsg> = (WasmMsg::Execute {
@@ -440,7 +891,35 @@ impl<'a> Lighthouse<'a> {
         let config = self.config.load(deps.storage)?;
         let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
 
-        let recipient = info.sender.clone();
+        if collection.paused_at.is_some() {
+            return Err(ContractError::CollectionPaused {});
+        }
+
+        if collection.escrow == Some(EscrowStatus::Cancelled) {
+            return Err(ContractError::CollectionCancelled {});
+        }
+
+        close_open_edition(&mut collection, env.block.time.seconds());
+
+        // The sender pays, the minter's identity is used for eligibility and limits,
+        // and the recipient receives the tokens (a gift recipient, the vault or the sender)
+        let payer = info.sender.clone();
+        let minter_info = self.resolve_minter(
+            &deps,
+            &env,
+            &payer,
+            &msg.collection,
+            msg.vault.clone(),
+            msg.evm_address_proof.clone()
+        )?;
+        let minter = minter_info.address.clone();
+
+        let recipient = match msg.recipient.clone() {
+            Some(recipient) => recipient,
+            None if msg.mint_to_vault == Some(true) => minter.to_string(),
+            None => payer.to_string(),
+        };
+        let recipient = self.resolve_recipient(&deps, &collection.chain, &recipient)?;
 
         // Find mint group
         let group_check = collection.mint_groups.iter().find(|&g| g.name == msg.group);
@@ -451,16 +930,12 @@ impl<'a> Lighthouse<'a> {
         let group = group_check.unwrap();
 
         // Check if sold out
-        if
-            collection.collection_type == "721" &&
-            collection.next_token + msg.amount - collection.start_order.unwrap_or(Uint128::zero()) >
-                collection.supply
-        {
-            return Err(ContractError::SoldOut {});
-        } else if
-            collection.collection_type == "404" &&
-            collection.next_token + msg.amount * group.batch_size.unwrap() > collection.supply
-        {
+        let token_amount = if collection.collection_type == "404" {
+            msg.amount * group.batch_size.unwrap()
+        } else {
+            msg.amount
+        };
+        if exceeds_supply(&collection, token_amount) {
             return Err(ContractError::SoldOut {});
         }
 
@@ -474,17 +949,28 @@ impl<'a> Lighthouse<'a> {
         }
 
         // Get the mint info for the group (if any) (mint count)
-        let mint_info_key = create_group_key(&recipient.to_string(), &msg.collection, &group.name);
+        let mint_info_key = create_group_key(
+            &minter_info.limit_owner,
+            &msg.collection,
+            &group.name
+        );
         let mut mint_info = self.mint_info
             .load(deps.storage, mint_info_key.clone())
             .unwrap_or(MintInfo {
                 mints: Vec::new(),
+                editions: None,
             });
 
+        // Presale reservations count towards the wallet limit and the reserved supply
+        let reserved = self.wallet_reserved
+            .may_load(deps.storage, mint_info_key.clone())?
+            .unwrap_or_default();
+
         // Check if the sender already minted the max tokens
         if
             group.max_mints_per_wallet.u128() != 0 &&
-            (mint_info.mints.len() as u128) + msg.amount.u128() > group.max_mints_per_wallet.u128()
+            mint_info.count() + reserved.u128() + msg.amount.u128() >
+                group.max_mints_per_wallet.u128()
         {
             return Err(ContractError::MaxTokensMinted {});
         }
@@ -494,113 +980,259 @@ impl<'a> Lighthouse<'a> {
         let mut global_mint_info = self.global_mint_info
             .load(deps.storage, global_mint_info_key.clone())
             .unwrap_or(Uint128::zero());
+        let reserved_total = self.reserved_totals
+            .may_load(deps.storage, global_mint_info_key.clone())?
+            .unwrap_or_default();
 
         // Check if the reserved supply ran out for the group
         if
             group.reserved_supply != Uint128::zero() &&
-            global_mint_info + msg.amount > group.reserved_supply
+            global_mint_info + reserved_total + msg.amount > group.reserved_supply
         {
             return Err(ContractError::ReservedSupplyRanOut {});
         }
 
-        //query recipient evm address
-        let querier: EvmQuerier<'_> = EvmQuerier::new(&deps.querier);
-        let recipient_evm_address_query = querier.query_evm_address(recipient.to_string())?;
+        // Validate merkle proof or allowlist (if any)
+        let allocation = self.check_eligibility(
+            deps.storage,
+            &msg.collection,
+            group,
+            &minter_info,
+            msg.merkle_proof.clone(),
+            msg.merkle_proof_address_type.clone()
+        )?;
+        if let Some(allocation) = allocation {
+            if mint_info.count() + reserved.u128() + msg.amount.u128() > allocation.u128() {
+                return Err(ContractError::MaxTokensMinted {});
+            }
+        }
 
-        if !recipient_evm_address_query.associated {
-            return Err(ContractError::NotAssociatedAddress {});
+        // Validate the mint voucher (if any)
+        if group.voucher_required == Some(true) && msg.voucher.is_none() {
+            return Err(ContractError::InvalidVoucher {});
         }
 
-        let recipient_evm_address = recipient_evm_address_query.evm_address;
+        let mut payments = group.payments.clone();
 
-        // Validate merkle proof (if any merkle root is set)
-        if group.merkle_root.is_some() {
-            if msg.merkle_proof.is_none() {
-                return Err(ContractError::InvalidMerkleProof {});
+        if let Some(voucher) = msg.voucher.clone() {
+            let signer = collection.voucher_signer
+                .clone()
+                .ok_or(ContractError::InvalidVoucher {})?;
+
+            if voucher.expiry < env.block.time.seconds() {
+                return Err(ContractError::VoucherExpired {});
             }
 
-            let mut recipient_addr = recipient.clone().to_string();
+            if msg.amount > voucher.max_amount {
+                return Err(ContractError::MaxTokensMinted {});
+            }
 
-            if msg.merkle_proof_address_type.is_some() {
-                if msg.merkle_proof_address_type.unwrap() == "hex" {
-                    recipient_addr = recipient_evm_address.to_lowercase();
-                }
+            let voucher_key = create_voucher_key(&msg.collection, voucher.nonce);
+            if self.used_vouchers.has(deps.storage, voucher_key.clone()) {
+                return Err(ContractError::VoucherAlreadyUsed {});
             }
 
-            // Check that the merkle proof and root is valid
-            let merkle_root = group.merkle_root.clone().unwrap();
-            if
-                !validate_merkle_proof(
-                    msg.merkle_proof.unwrap(),
-                    merkle_root,
-                    Keccak256::digest(&recipient_addr.as_bytes()).to_vec()
-                )
-            {
-                return Err(ContractError::InvalidMerkleProof {});
+            let voucher_hash = create_voucher_hash(
+                env.contract.address.as_ref(),
+                &env.block.chain_id,
+                &msg.collection,
+                &group.name,
+                minter.as_ref(),
+                &voucher
+            );
+            let valid = deps.api
+                .secp256k1_verify(&voucher_hash, &voucher.signature, &signer)
+                .map_err(|_| ContractError::InvalidVoucher {})?;
+            if !valid {
+                return Err(ContractError::InvalidVoucher {});
+            }
+
+            self.used_vouchers.save(deps.storage, voucher_key, &true)?;
+
+            // The voucher price replaces the group's native payments
+            if let Some(price) = voucher.price {
+                let payee = payments
+                    .iter()
+                    .find(|p| p.payment_type == PaymentType::Native)
+                    .map(|p| p.args[0].clone())
+                    .unwrap_or(collection.admin.to_string());
+
+                payments.retain(|p| p.payment_type != PaymentType::Native);
+                if !price.is_zero() {
+                    payments.push(Payment {
+                        payment_type: PaymentType::Native,
+                        amount: Some(price),
+                        args: vec![payee],
+                    });
+                }
             }
         }
 
         // get the total native and cw20 payments
         let total_native_payment =
-            group.payments
+            payments
                 .iter()
                 .filter(|&p| p.payment_type == PaymentType::Native)
                 .fold(Uint128::zero(), |acc, p| acc + p.amount.unwrap()) * msg.amount;
 
         let total_cw20_payment =
-            group.payments
+            payments
                 .iter()
                 .filter(|&p| p.payment_type == PaymentType::Cw20)
                 .fold(Uint128::zero(), |acc, p| acc + p.amount.unwrap()) * msg.amount;
 
+        // Prepare the response
+        let mut response: Response<EvmMsg> = Response::new();
+
+        // Raffle groups can only be minted by winners, with their deposit counted as payment
+        let mut deposit_credit = Uint128::zero();
+        if let Some(raffle) = &group.raffle {
+            let raffle_key = create_raffle_key(&msg.collection, &group.name);
+            let state = self.raffles
+                .may_load(deps.storage, raffle_key.clone())?
+                .ok_or(ContractError::RaffleNotDrawn {})?;
+            if !state.is_drawn() {
+                return Err(ContractError::RaffleNotDrawn {});
+            }
+
+            let ticket_key = (raffle_key, minter.to_string());
+            let mut ticket = self.raffle_tickets
+                .may_load(deps.storage, ticket_key.clone())?
+                .ok_or(ContractError::NotRaffleWinner {})?;
+
+            if !ticket.won || ticket.minted || ticket.refunded || msg.amount != Uint128::one() {
+                return Err(ContractError::NotRaffleWinner {});
+            }
+
+            ticket.minted = true;
+            self.raffle_tickets.save(deps.storage, ticket_key, &ticket)?;
+
+            let deposit = raffle.deposit.unwrap_or_default();
+            let required = if total_native_payment.is_zero() {
+                Uint128::zero()
+            } else {
+                total_native_payment + config.fee * msg.amount
+            };
+            deposit_credit = deposit.min(required);
+
+            // Refund the part of the deposit the price does not use
+            if deposit > deposit_credit {
+                response = response.add_message(BankMsg::Send {
+                    to_address: payer.to_string(),
+                    amount: coins((deposit - deposit_credit).u128(), "usei"),
+                });
+            }
+        }
+
         if total_native_payment > Uint128::zero() {
             // Check if the sender have enough funds
-            if
+            let expected = total_native_payment + config.fee * msg.amount - deposit_credit;
+            if expected.is_zero() {
+                if !info.funds.is_empty() {
+                    return Err(ContractError::InvalidFunds {});
+                }
+            } else if
                 info.funds.len() != 1 ||
                 info.funds[0].denom != "usei" ||
-                info.funds[0].amount != total_native_payment + config.fee * msg.amount
+                info.funds[0].amount != expected
             {
                 return Err(ContractError::InvalidFunds {});
             }
         }
 
-        // Prepare the response
-        let mut response: Response<EvmMsg> = Response::new();
         let mut attrs: Vec<Attribute> = Vec::new();
 
-        for payment in group.payments.clone() {
-            match payment.payment_type {
-                PaymentType::Native => {
-                    // Transfer the funds to the destination wallet
-                    let total = payment.amount.unwrap().u128() * msg.amount.u128();
-                    response = response.add_message(BankMsg::Send {
-                        to_address: payment.args[0].clone(),
-                        amount: coins(total, "usei"),
-                    });
+        // In escrow mode the proceeds (and the platform fee) are held by Lighthouse until the
+        // collection is finalized, with vesting a share of them is held and released to the
+        // payee over time,
+        // and with a return window the refundable share is held per token until it closes
+        let escrow = collection.escrow == Some(EscrowStatus::Active);
+        let held_percent = if escrow {
+            100
+        } else if let Some(vesting) = &collection.vesting {
+            vesting.held_percent
+        } else if let Some(return_window) = &collection.return_window {
+            return_window.refund_percent
+        } else {
+            0
+        };
+        let mut returnable: Vec<HeldPayment> = Vec::new();
 
-                    attrs.push(Attribute {
-                        key: format!("paid_sei"),
-                        value: total.to_string(),
-                    });
-                }
-                PaymentType::Cw20 => {
+        for payment in payments {
+            match payment.payment_type {
+                PaymentType::Native | PaymentType::Cw20 => {
                     // Transfer the funds to the destination wallet
                     let total = payment.amount.unwrap() * msg.amount;
-                    response = response.add_message(WasmMsg::Execute {
-                        contract_addr: payment.args[1].clone(),
-                        msg: to_json_binary(
-                            &(Cw20ExecuteMsg::TransferFrom {
-                                owner: recipient.to_string(),
-                                recipient: payment.args[0].clone(),
-                                amount: total,
-                            })
-                        )?,
-                        funds: vec![],
-                    });
-                    attrs.push(Attribute {
-                        key: format!("paid_{}", payment.args[1]),
-                        value: total.to_string(),
-                    });
+                    let held_per_token = payment.amount
+                        .unwrap()
+                        .multiply_ratio(held_percent, 100u64);
+                    let held = held_per_token * msg.amount;
+                    let payee = payment.args[0].clone();
+                    let denom = if payment.payment_type == PaymentType::Native {
+                        "usei".to_string()
+                    } else {
+                        payment.args[1].clone()
+                    };
+
+                    if !held.is_zero() {
+                        if escrow {
+                            self.hold_payment(
+                                deps.storage,
+                                &msg.collection,
+                                payer.as_ref(),
+                                &payee,
+                                &denom,
+                                held
+                            )?;
+                        } else if collection.vesting.is_some() {
+                            self.hold_vesting(deps.storage, &msg.collection, &payee, &denom, held)?;
+                        } else {
+                            returnable.push(HeldPayment {
+                                payee: payee.clone(),
+                                denom: denom.clone(),
+                                amount: held_per_token,
+                            });
+                        }
+                    }
+
+                    if payment.payment_type == PaymentType::Native {
+                        if !(total - held).is_zero() {
+                            response = response.add_message(BankMsg::Send {
+                                to_address: payee,
+                                amount: coins((total - held).u128(), "usei"),
+                            });
+                        }
+
+                        attrs.push(Attribute {
+                            key: "paid_sei".to_string(),
+                            value: total.to_string(),
+                        });
+                    } else {
+                        for (to, amount) in [
+                            (payee, total - held),
+                            (env.contract.address.to_string(), held),
+                        ] {
+                            if amount.is_zero() {
+                                continue;
+                            }
+                            response = response.add_message(WasmMsg::Execute {
+                                contract_addr: denom.clone(),
+                                msg: to_json_binary(
+                                    &(Cw20ExecuteMsg::TransferFrom {
+                                        owner: payer.to_string(),
+                                        recipient: to,
+                                        amount,
+                                    })
+                                )?,
+                                funds: vec![],
+                            });
+                        }
+
+                        attrs.push(Attribute {
+                            key: format!("paid_{}", denom),
+                            value: total.to_string(),
+                        });
+                    }
                 }
                 PaymentType::Cw20Burn => {
                     // Burn the funds
@@ -609,7 +1241,7 @@ impl<'a> Lighthouse<'a> {
                         contract_addr: payment.args[0].clone(),
                         msg: to_json_binary(
                             &(Cw20ExecuteMsg::BurnFrom {
-                                owner: recipient.to_string(),
+                                owner: payer.to_string(),
                                 amount: total,
                             })
                         )?,
@@ -627,98 +1259,52 @@ impl<'a> Lighthouse<'a> {
         }
 
         if total_native_payment > Uint128::zero() || total_cw20_payment > Uint128::zero() {
-            let mut admin_fee = config.fee.u128() * msg.amount.u128();
-            // Transfer the admin fee to the collection admin and partner (if any)
-            if collection.partner.is_some() {
-                let partner = self.partners.may_load(
+            response = response.add_messages(
+                self.create_fee_msgs(
                     deps.storage,
-                    collection.partner.clone().unwrap().to_string()
-                )?;
-                if partner.is_some() {
-                    let partner_data = partner.unwrap();
-                    if partner_data.fee_percent > Uint128::zero() {
-                        let partner_fee =
-                            (config.fee.u128() * partner_data.fee_percent.u128()) / 100; //calculate partner fee
-                        response = response.add_message(BankMsg::Send {
-                            to_address: partner_data.partner.to_string(),
-                            amount: coins(partner_fee, "usei"),
-                        });
-
-                        admin_fee -= partner_fee; //deduct partner fee from admin fee
-                    }
-                }
-            }
-
-            response = response.add_message(BankMsg::Send {
-                to_address: config.admin.to_string(),
-                amount: coins(admin_fee, "usei"),
-            });
-        }
+                    &collection,
+                    &msg.collection,
+                    payer.as_ref(),
+                    config.fee,
+                    msg.amount
+                )?
+            );
+        }
 
         //mint
         if collection.collection_type == "721" {
-            if collection.chain == "v1" {
-                // Init royalty extension
-                let extension = Some(Cw2981Metadata {
-                    ..Cw2981Metadata::default()
-                });
-
-                for order in 0..msg.amount.u128() {
-                    // Prepare the mint message
-                    let mint_msg = Cw2981ExecuteMsg::Mint {
-                        token_id: (collection.next_token.u128() + order).to_string(),
-                        owner: recipient.to_string(),
-                        token_uri: None,
-                        extension: extension.clone(),
-                    };
-
-                    //call cw721 contract
-                    let callback = WasmMsg::Execute {
-                        contract_addr: msg.collection.clone(),
-                        msg: to_json_binary(&mint_msg)?,
-                        funds: vec![],
-                    };
-
-                    response = response.add_message(callback.clone());
-                }
-            } else {
-                //call collection contract
-                let selector = &Keccak256::digest(b"mint(address,uint256)")[0..4];
-
-                let account_padded = pad_address_to_bytes32(recipient_evm_address.as_str())?;
-
-                for order in 0..msg.amount.u128() {
-                    let mut token_id_bytes = [0u8; 32];
-                    (collection.next_token.u128() + order)
-                        .to_be_bytes()
-                        .iter()
-                        .enumerate()
-                        .for_each(|(i, &b)| {
-                            token_id_bytes[32 - 16 + i] = b; //pad token id to 32 bytes
-                        });
-
-                    let data = [&selector[..], &account_padded[..], &token_id_bytes[..]].concat();
-                    let data_base64 = BASE64.encode(&data);
+            let token_ids = self.next_token_ids(
+                deps.storage,
+                &env,
+                &collection,
+                minter.as_ref(),
+                msg.amount,
+                &mut attrs
+            )?;
+            response = response.add_messages(
+                self.create_721_mint_msgs(&collection, &recipient, &token_ids)?
+            );
 
-                    response = response.add_message(EvmMsg::CallEvm {
-                        to: collection.collection_address.clone().unwrap(),
-                        value: Uint128::zero(),
-                        data: data_base64.clone(),
-                    });
-                }
-            }
             // save states
-            for i in 0..msg.amount.u128() {
-                self.mint_logs.save(
-                    deps.storage,
-                    create_mint_log_key(
-                        &msg.collection,
-                        &(collection.next_token.u128() + i).to_string()
-                    ),
-                    &recipient.to_string()
-                )?;
+            for token_id in token_ids.iter() {
+                let mint_log_key = create_mint_log_key(&msg.collection, &token_id.to_string());
+                self.mint_logs.save(deps.storage, mint_log_key.clone(), &payer.to_string())?;
+                self.recipient_logs.save(deps.storage, mint_log_key.clone(), &recipient)?;
+
+                if let Some(return_window) = &collection.return_window {
+                    if !returnable.is_empty() {
+                        self.token_payments.save(
+                            deps.storage,
+                            mint_log_key,
+                            &(TokenPayment {
+                                returnable_until: env.block.time.seconds() + return_window.duration,
+                                held: returnable.clone(),
+                            })
+                        )?;
+                    }
+                }
 
-                mint_info.mints.push(collection.next_token + Uint128::from(i));
+                mint_info.mints.push(Uint128::from(*token_id));
             }
 
             self.mint_info.save(deps.storage, mint_info_key, &mint_info)?;
@@ -728,39 +1314,55 @@ impl<'a> Lighthouse<'a> {
 
             attrs.push(Attribute {
                 key: "token_ids".to_string(),
-                value: (collection.next_token.u128()..collection.next_token.u128() +
-                    msg.amount.u128())
+                value: token_ids
+                    .iter()
                     .map(|i| i.to_string())
                     .collect::<Vec<String>>()
                     .join(", "),
             });
             collection.next_token += msg.amount;
-        } else {
-            let batch = group.batch_size.clone().unwrap();
-            let mint_msg = Cw404ExecuteMsg::Mint {
-                recipient: recipient.to_string(),
-                amount: batch,
-            };
+        } else if collection.collection_type == "1155" {
+            let edition_id = group.edition_id.unwrap();
+            response = response.add_message(
+                self.create_1155_mint_msg(&collection, &recipient, edition_id, msg.amount)?
+            );
+
+            // save states, logged under the index of the first edition unit minted
+            let mint_log_key = create_mint_log_key_404(
+                &msg.collection,
+                &group.name,
+                &global_mint_info.to_string()
+            );
+            self.mint_logs.save(deps.storage, mint_log_key.clone(), &payer.to_string())?;
+            self.recipient_logs.save(deps.storage, mint_log_key, &recipient)?;
+
+            mint_info.add_edition(edition_id, msg.amount);
 
-            let callback = WasmMsg::Execute {
-                contract_addr: msg.collection.clone(),
-                msg: to_json_binary(&mint_msg)?,
-                funds: vec![],
-            };
+            self.mint_info.save(deps.storage, mint_info_key, &mint_info)?;
+
+            global_mint_info += msg.amount;
+            self.global_mint_info.save(deps.storage, global_mint_info_key, &global_mint_info)?;
+
+            attrs.push(Attribute {
+                key: "edition_id".to_string(),
+                value: edition_id.to_string(),
+            });
+            collection.next_token += msg.amount;
+        } else {
+            let batch = group.batch_size.unwrap();
+            let callback = self.create_404_mint_msg(&collection, &recipient, batch)?;
 
             for i in 0..msg.amount.u128() {
                 response = response.add_message(callback.clone());
 
                 // save states
-                self.mint_logs.save(
-                    deps.storage,
-                    create_mint_log_key_404(
-                        &msg.collection,
-                        &group.name,
-                        &(global_mint_info.u128() + i).to_string()
-                    ),
-                    &recipient.to_string()
-                )?;
+                let mint_log_key = create_mint_log_key_404(
+                    &msg.collection,
+                    &group.name,
+                    &(global_mint_info.u128() + i).to_string()
+                );
+                self.mint_logs.save(deps.storage, mint_log_key.clone(), &payer.to_string())?;
+                self.recipient_logs.save(deps.storage, mint_log_key, &recipient)?;
 
                 mint_info.mints.push(batch);
             }
@@ -777,6 +1379,10 @@ impl<'a> Lighthouse<'a> {
             collection.next_token += batch * msg.amount;
         }
 
+        if exceeds_supply(&collection, Uint128::one()) {
+            self.reach_milestone(&mut collection, Milestone::SoldOut);
+        }
+
         // save collection
         self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
 
@@ -786,7 +1392,9 @@ impl<'a> Lighthouse<'a> {
                 .add_attribute("chain", collection.chain)
                 .add_attribute("collection", msg.collection)
                 .add_attribute("group", msg.group)
-                .add_attribute("recipient", recipient.to_string())
+                .add_attribute("payer", payer.to_string())
+                .add_attribute("minter", minter.to_string())
+                .add_attribute("recipient", recipient)
                 .add_attribute("mint_amount", msg.amount.to_string())
                 .add_attributes(attrs)
         )
@@ -795,82 +1403,96 @@ impl<'a> Lighthouse<'a> {
     pub fn unfreeze_collection(
         &self,
         deps: DepsMut<EvmQueryWrapper>,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         msg: UnfreezeCollection
     ) -> Result<Response<EvmMsg>, ContractError> {
         let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
 
-        if collection.admin != info.sender {
+        // Anyone can unfreeze once the unfreeze time has passed, the admin can unfreeze early
+        let unfreeze_time_passed = collection.unfreeze_time
+            .map(|t| t <= env.block.time.seconds())
+            .unwrap_or(false);
+
+        if collection.admin != info.sender && (!unfreeze_time_passed || !collection.frozen) {
             return Err(ContractError::Unauthorized {});
         }
 
+        if collection.chain == "v1" && collection.collection_type == "1155" {
+            return Err(ContractError::NotImplemented {});
+        }
+
+        if collection.is_stock_cw721() {
+            return Err(ContractError::UnsupportedByStockCw721 {});
+        }
+
         collection.frozen = false;
         self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
 
-        if collection.chain == "v1" && collection.collection_type == "721" {
-            let execute: CosmosMsg<EvmMsg> = (WasmMsg::Execute {
+        let execute: CosmosMsg<EvmMsg> = if
+            collection.chain == "v1" &&
+            collection.collection_type == "721"
+        {
+            (WasmMsg::Execute {
                 contract_addr: msg.collection.clone(),
                 msg: to_json_binary(
                     &(Cw2981LHExecuteMsg::Extension { msg: Cw2981LHExecuteExtension::Unfreeze {} })
                 )?,
                 funds: vec![],
-            }).into();
-
-            Ok(
-                Response::<EvmMsg>
-                    ::new()
-                    .add_message(execute)
-                    .add_attribute("action", "unfreeze")
-                    .add_attribute("collection", msg.collection)
-            )
+            }).into()
         } else if collection.chain == "v1" && collection.collection_type == "404" {
-            let execute: CosmosMsg<EvmMsg> = (WasmMsg::Execute {
+            (WasmMsg::Execute {
                 contract_addr: msg.collection.clone(),
                 msg: to_json_binary(&(Cw404ExecuteMsg::Unfreeze {}))?,
                 funds: vec![],
-            }).into();
-
-            Ok(
-                Response::<EvmMsg>
-                    ::new()
-                    .add_message(execute)
-                    .add_attribute("action", "unfreeze")
-                    .add_attribute("collection", msg.collection)
-            )
+            }).into()
         } else {
             let selector = &Keccak256::digest(b"unfreeze()")[0..4];
             let data_base64 = BASE64.encode(selector);
 
-            let execute = EvmMsg::CallEvm {
+            (EvmMsg::CallEvm {
                 to: collection.collection_address.clone().unwrap(),
                 value: Uint128::zero(),
                 data: data_base64,
-            };
+            }).into()
+        };
 
-            Ok(
-                Response::<EvmMsg>
-                    ::new()
-                    .add_message(execute)
-                    .add_attribute("action", "unfreeze")
-                    .add_attribute("collection", msg.collection)
-            )
-        }
+        Ok(
+            Response::<EvmMsg>
+                ::new()
+                .add_message(execute)
+                .add_attribute("action", "unfreeze")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("sender", info.sender)
+                .add_attribute("early_unfreeze", (!unfreeze_time_passed).to_string())
+        )
     }
 
     pub fn reveal_collection_metadata(
         &self,
         deps: DepsMut<EvmQueryWrapper>,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         msg: RevealCollectionMetadata
     ) -> Result<Response<EvmMsg>, ContractError> {
-        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.is_stock_cw721() {
+            return Err(ContractError::UnsupportedByStockCw721 {});
+        }
 
+        // Anyone can reveal a hidden collection once its reveal time has passed
         if collection.admin != info.sender {
-            return Err(ContractError::Unauthorized {});
+            let reveal_time = collection.reveal_time.ok_or(ContractError::Unauthorized {})?;
+            if reveal_time > env.block.time.seconds() || !collection.hidden_metadata {
+                return Err(ContractError::Unauthorized {});
+            }
         }
 
+        collection.hidden_metadata = false;
+        self.reach_milestone(&mut collection, Milestone::Reveal);
+        self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
         if collection.chain == "v1" {
             let execute: CosmosMsg<EvmMsg> = (WasmMsg::Execute {
                 contract_addr: msg.collection.clone(),
@@ -932,6 +1554,107 @@ impl<'a> Lighthouse<'a> {
         )
     }
 
+    pub fn approve_code(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: ApproveCode
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+
+        if config.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let kind = create_code_registry_key(&msg.collection_type, &msg.cw721_flavor);
+
+        // Only one code per kind is the default
+        if msg.default {
+            let defaults = self.approved_codes
+                .prefix(kind.clone())
+                .range(deps.storage, None, None, Order::Ascending)
+                .filter(|item| item.as_ref().map(|(_, c)| c.default).unwrap_or(false))
+                .collect::<StdResult<Vec<(u64, ApprovedCode)>>>()?;
+            for (code_id, mut code) in defaults {
+                code.default = false;
+                self.approved_codes.save(deps.storage, (kind.clone(), code_id), &code)?;
+            }
+        }
+
+        self.approved_codes.save(
+            deps.storage,
+            (kind.clone(), msg.code_id),
+            &(ApprovedCode {
+                kind: kind.clone(),
+                code_id: msg.code_id,
+                label: msg.label.clone(),
+                default: msg.default,
+            })
+        )?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "approve_code")
+                .add_attribute("kind", kind)
+                .add_attribute("code_id", msg.code_id.to_string())
+                .add_attribute("label", msg.label)
+                .add_attribute("default", msg.default.to_string())
+        )
+    }
+
+    pub fn revoke_code(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: RevokeCode
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+
+        if config.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let kind = create_code_registry_key(&msg.collection_type, &msg.cw721_flavor);
+        self.approved_codes.remove(deps.storage, (kind.clone(), msg.code_id));
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "revoke_code")
+                .add_attribute("kind", kind)
+                .add_attribute("code_id", msg.code_id.to_string())
+        )
+    }
+
+    pub fn remove_pending_registration(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: RemovePendingRegistration
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+        let pending = self.instantiates
+            .may_load(deps.storage, msg.reply_id)?
+            .ok_or(ContractError::UnknownReplyId {})?;
+
+        if config.admin != info.sender && pending.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // Entries with a predicted address were registered up front and stay registered,
+        // only their pending entry is dropped
+        self.instantiates.remove(deps.storage, msg.reply_id);
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "remove_pending_registration")
+                .add_attribute("reply_id", msg.reply_id.to_string())
+                .add_attribute("sender", info.sender)
+        )
+    }
+
     pub fn add_partner(
         &self,
         deps: DepsMut<EvmQueryWrapper>,
@@ -1009,7 +1732,7 @@ impl<'a> Lighthouse<'a> {
 
             let account_padded = pad_address_to_bytes32(&new_admin)?;
 
-            let data = [&selector[..], &account_padded[..]].concat();
+            let data = [selector, &account_padded[..]].concat();
             let data_base64 = BASE64.encode(&data);
 
             let execute = EvmMsg::CallEvm {
@@ -1066,6 +1789,8 @@ impl<'a> Lighthouse<'a> {
                     .add_attribute("collection", msg.collection)
                     .add_attribute("new_admin", msg.new_admin)
             )
+        } else if collection.collection_type == "1155" {
+            Err(ContractError::NotImplemented {})
         } else {
             //404
             let execute: CosmosMsg<EvmMsg> = (WasmMsg::Execute {
@@ -1089,5 +1814,2357 @@ impl<'a> Lighthouse<'a> {
         }
     }
 
-   
+    pub fn add_to_allowlist(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: AddToAllowlist
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if !collection.mint_groups.iter().any(|g| g.name == msg.group) {
+            return Err(ContractError::InvalidMintGroup {});
+        }
+
+        for entry in msg.entries.iter() {
+            let address = normalize_address(&deps, &entry.address)?;
+            self.allowlists.save(
+                deps.storage,
+                (msg.collection.clone(), msg.group.clone(), address.clone()),
+                &(AllowlistEntry {
+                    address,
+                    allocation: entry.allocation,
+                })
+            )?;
+        }
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "add_to_allowlist")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("group", msg.group)
+                .add_attribute("count", msg.entries.len().to_string())
+        )
+    }
+
+    pub fn remove_from_allowlist(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: RemoveFromAllowlist
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if !collection.mint_groups.iter().any(|g| g.name == msg.group) {
+            return Err(ContractError::InvalidMintGroup {});
+        }
+
+        for address in msg.addresses.iter() {
+            let normalized = normalize_address(&deps, address)?;
+            self.allowlists.remove(
+                deps.storage,
+                (msg.collection.clone(), msg.group.clone(), normalized)
+            );
+
+            // Hex entries added before the address was associated are stored under the raw hex
+            if address.starts_with("0x") {
+                self.allowlists.remove(
+                    deps.storage,
+                    (msg.collection.clone(), msg.group.clone(), address.to_lowercase())
+                );
+            }
+        }
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "remove_from_allowlist")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("group", msg.group)
+                .add_attribute("count", msg.addresses.len().to_string())
+        )
+    }
+
+    pub fn delegate_wallet(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: DelegateWallet
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        // Hex delegates resolve to their associated bech32 wallet, which is what mints
+        let delegate = deps.api.addr_validate(&normalize_address(&deps, &msg.delegate)?)?;
+
+        if let Some(collection) = msg.collection.clone() {
+            if !self.collections.has(deps.storage, collection) {
+                return Err(ContractError::Std(StdError::not_found("Collection")));
+            }
+        }
+
+        self.delegations.save(
+            deps.storage,
+            (
+                info.sender.to_string(),
+                create_delegation_key(delegate.as_ref(), msg.collection.as_deref()),
+            ),
+            &(Delegation {
+                vault: info.sender.clone(),
+                delegate: delegate.clone(),
+                collection: msg.collection.clone(),
+            })
+        )?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "delegate_wallet")
+                .add_attribute("vault", info.sender)
+                .add_attribute("delegate", delegate)
+                .add_attribute("collection", msg.collection.unwrap_or("all".to_string()))
+        )
+    }
+
+    pub fn revoke_delegation(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: RevokeDelegation
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        // Normalized like in DelegateWallet, so casing or hex/bech32 form don't matter
+        let delegate = normalize_address(&deps, &msg.delegate)?;
+        let key = (
+            info.sender.to_string(),
+            create_delegation_key(&delegate, msg.collection.as_deref()),
+        );
+
+        if !self.delegations.has(deps.storage, key.clone()) {
+            return Err(ContractError::NotDelegated {});
+        }
+
+        self.delegations.remove(deps.storage, key);
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "revoke_delegation")
+                .add_attribute("vault", info.sender)
+                .add_attribute("delegate", delegate)
+                .add_attribute("collection", msg.collection.unwrap_or("all".to_string()))
+        )
+    }
+
+    pub fn admin_mint(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: AdminMint
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if collection.escrow == Some(EscrowStatus::Cancelled) {
+            return Err(ContractError::CollectionCancelled {});
+        }
+
+        close_open_edition(&mut collection, env.block.time.seconds());
+
+        // Check if sold out
+        let total = msg.mints.iter().fold(Uint128::zero(), |acc, m| acc + m.amount);
+        if exceeds_supply(&collection, total) {
+            return Err(ContractError::SoldOut {});
+        }
+
+        let global_mint_info_key = create_global_mint_info_key(&msg.collection, ADMIN_MINT_GROUP);
+        let mut global_mint_info = self.global_mint_info
+            .load(deps.storage, global_mint_info_key.clone())
+            .unwrap_or(Uint128::zero());
+
+        let mut response: Response<EvmMsg> = Response::new();
+        let mut attrs: Vec<Attribute> = Vec::new();
+
+        for entry in msg.mints.iter() {
+            let recipient = self.resolve_recipient(&deps, &collection.chain, &entry.recipient)?;
+
+            if collection.collection_type == "721" {
+                let token_ids = self.next_token_ids(
+                    deps.storage,
+                    &env,
+                    &collection,
+                    &recipient,
+                    entry.amount,
+                    &mut attrs
+                )?;
+                response = response.add_messages(
+                    self.create_721_mint_msgs(&collection, &recipient, &token_ids)?
+                );
+
+                for token_id in token_ids {
+                    let mint_log_key = create_mint_log_key(&msg.collection, &token_id.to_string());
+                    let sender = info.sender.to_string();
+                    self.mint_logs.save(deps.storage, mint_log_key.clone(), &sender)?;
+                    self.recipient_logs.save(deps.storage, mint_log_key, &recipient)?;
+                }
+
+                global_mint_info += entry.amount;
+            } else if collection.collection_type == "1155" {
+                let edition_id = entry.edition_id.ok_or(ContractError::InvalidTokenId {})?;
+                response = response.add_message(
+                    self.create_1155_mint_msg(&collection, &recipient, edition_id, entry.amount)?
+                );
+
+                let mint_log_key = create_mint_log_key_404(
+                    &msg.collection,
+                    ADMIN_MINT_GROUP,
+                    &global_mint_info.to_string()
+                );
+                self.mint_logs.save(deps.storage, mint_log_key.clone(), &info.sender.to_string())?;
+                self.recipient_logs.save(deps.storage, mint_log_key, &recipient)?;
+
+                global_mint_info += entry.amount;
+            } else {
+                response = response.add_message(
+                    self.create_404_mint_msg(&collection, &recipient, entry.amount)?
+                );
+
+                let mint_log_key = create_mint_log_key_404(
+                    &msg.collection,
+                    ADMIN_MINT_GROUP,
+                    &global_mint_info.to_string()
+                );
+                self.mint_logs.save(deps.storage, mint_log_key.clone(), &info.sender.to_string())?;
+                self.recipient_logs.save(deps.storage, mint_log_key, &recipient)?;
+
+                global_mint_info += Uint128::one();
+            }
+
+            collection.next_token += entry.amount;
+        }
+
+        if exceeds_supply(&collection, Uint128::one()) {
+            self.reach_milestone(&mut collection, Milestone::SoldOut);
+        }
+
+        self.global_mint_info.save(deps.storage, global_mint_info_key, &global_mint_info)?;
+        self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
+        Ok(
+            response
+                .add_attribute("action", "admin_mint")
+                .add_attribute("chain", collection.chain)
+                .add_attribute("collection", msg.collection)
+                .add_attribute("recipients", msg.mints.len().to_string())
+                .add_attribute("mint_amount", total.to_string())
+                .add_attributes(attrs)
+        )
+    }
+
+    pub fn reveal_random_seed(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: RevealRandomSeed
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if collection.random_seed.is_some() {
+            return Err(ContractError::InvalidRandomSeed {});
+        }
+
+        let commitment = collection.random_seed_commitment
+            .clone()
+            .ok_or(ContractError::InvalidRandomSeed {})?;
+
+        if Keccak256::digest(msg.seed.as_slice()).as_slice() != commitment.as_slice() {
+            return Err(ContractError::InvalidRandomSeed {});
+        }
+
+        collection.random_seed = Some(msg.seed.clone());
+        self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "reveal_random_seed")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("seed", msg.seed.to_base64())
+        )
+    }
+
+    pub fn pause_collection(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: PauseCollection
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        // The platform admin can pause any collection
+        if collection.admin != info.sender && config.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if collection.paused_at.is_some() {
+            return Err(ContractError::CollectionPaused {});
+        }
+
+        collection.paused_at = Some(env.block.time.seconds());
+        collection.paused_by = Some(info.sender.clone());
+        self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "pause_collection")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("sender", info.sender)
+        )
+    }
+
+    pub fn resume_collection(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: ResumeCollection
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.admin != info.sender && config.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // A pause set by the platform admin overrides the collection admin
+        if
+            config.admin != info.sender &&
+            collection.paused_by.as_ref().is_some_and(|by| *by == config.admin)
+        {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        collection.paused_at = None;
+        collection.paused_by = None;
+        self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "resume_collection")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("sender", info.sender)
+        )
+    }
+
+    pub fn finalize_collection(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: FinalizeCollection
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if collection.escrow != Some(EscrowStatus::Active) {
+            return Err(ContractError::InvalidEscrowStatus {});
+        }
+
+        collection.escrow = Some(EscrowStatus::Finalized);
+        self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
+        // Release the held proceeds to the payment recipients
+        let balances = self.escrow_balances
+            .sub_prefix(msg.collection.clone())
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<((String, String), Uint128)>>>()?;
+
+        let mut response: Response<EvmMsg> = Response::new();
+        for ((payee, denom), amount) in balances {
+            self.escrow_balances.remove(
+                deps.storage,
+                (msg.collection.clone(), payee.clone(), denom.clone())
+            );
+            if !amount.is_zero() {
+                response = response.add_message(create_payout_msg(&denom, &payee, amount)?);
+            }
+        }
+
+        Ok(
+            response
+                .add_attribute("action", "finalize_collection")
+                .add_attribute("collection", msg.collection)
+        )
+    }
+
+    pub fn cancel_collection(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: CancelCollection
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        // The platform admin can cancel any collection
+        if collection.admin != info.sender && config.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if collection.escrow != Some(EscrowStatus::Active) {
+            return Err(ContractError::InvalidEscrowStatus {});
+        }
+
+        collection.escrow = Some(EscrowStatus::Cancelled);
+        self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "cancel_collection")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("sender", info.sender)
+        )
+    }
+
+    pub fn claim_refund(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: ClaimRefund
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.escrow != Some(EscrowStatus::Cancelled) {
+            return Err(ContractError::InvalidEscrowStatus {});
+        }
+
+        let payments = self.escrow_payments
+            .prefix((msg.collection.clone(), info.sender.to_string()))
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+
+        if payments.is_empty() {
+            return Err(ContractError::NothingToClaim {});
+        }
+
+        let mut response: Response<EvmMsg> = Response::new();
+        for (denom, amount) in payments {
+            self.escrow_payments.remove(
+                deps.storage,
+                (msg.collection.clone(), info.sender.to_string(), denom.clone())
+            );
+            response = response
+                .add_message(create_payout_msg(&denom, info.sender.as_ref(), amount)?)
+                .add_attribute(format!("refunded_{}", denom), amount);
+        }
+
+        Ok(
+            response
+                .add_attribute("action", "claim_refund")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("recipient", info.sender)
+        )
+    }
+
+    pub fn claim_proceeds(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: ClaimProceeds
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+        let vesting = collection.vesting.ok_or(ContractError::NothingToClaim {})?;
+        let reached = collection.reached_milestones.unwrap_or_default();
+
+        let balances = self.vesting_balances
+            .prefix((msg.collection.clone(), info.sender.to_string()))
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(String, VestingBalance)>>>()?;
+
+        let mut response: Response<EvmMsg> = Response::new();
+        for (denom, mut balance) in balances {
+            let vested = vested_amount(&vesting, &reached, balance.total, env.block.time.seconds());
+            let claimable = vested - balance.claimed;
+            if claimable.is_zero() {
+                continue;
+            }
+
+            balance.claimed = vested;
+            self.vesting_balances.save(
+                deps.storage,
+                (msg.collection.clone(), info.sender.to_string(), denom.clone()),
+                &balance
+            )?;
+            response = response
+                .add_message(create_payout_msg(&denom, info.sender.as_ref(), claimable)?)
+                .add_attribute(format!("claimed_{}", denom), claimable);
+        }
+
+        if response.messages.is_empty() {
+            return Err(ContractError::NothingToClaim {});
+        }
+
+        Ok(
+            response
+                .add_attribute("action", "claim_proceeds")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("recipient", info.sender)
+        )
+    }
+
+    /// Returns a token sent with cw721 SendNft, the token is then owned by Lighthouse.
+    pub fn receive_nft(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw721ReceiveMsg
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let holder = deps.api.addr_validate(&msg.sender)?;
+        self.refund_return(deps, env, info.sender.to_string(), holder.to_string(), msg.token_id)
+    }
+
+    /// Returns a token Lighthouse is approved for, by burning it from the holder.
+    pub fn return_token(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: ReturnToken
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let owner: Cw721OwnerOfResponse = deps.querier.query_wasm_smart(
+            msg.collection.clone(),
+            &(Cw721QueryMsg::OwnerOf {
+                token_id: msg.token_id.clone(),
+                include_expired: None,
+            })
+        )?;
+
+        if owner.owner != info.sender {
+            return Err(ContractError::InvalidOwnerOfNft {});
+        }
+
+        self.refund_return(deps, env, msg.collection, info.sender.to_string(), msg.token_id)
+    }
+
+    /// Releases the held refunds of tokens whose return window has closed to the payees.
+    pub fn release_return_funds(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        _info: MessageInfo,
+        msg: ReleaseReturnFunds
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let mut response: Response<EvmMsg> = Response::new();
+        let mut released: Vec<String> = Vec::new();
+
+        for token_id in msg.token_ids.iter() {
+            // Tokens that were returned or already released have no entry, skip them
+            let key = create_mint_log_key(&msg.collection, token_id);
+            let payment = match self.token_payments.may_load(deps.storage, key.clone())? {
+                Some(payment) => payment,
+                None => {
+                    continue;
+                }
+            };
+
+            if payment.returnable_until >= env.block.time.seconds() {
+                return Err(ContractError::Std(StdError::generic_err("Return window is open")));
+            }
+
+            self.token_payments.remove(deps.storage, key);
+            for held in payment.held {
+                response = response.add_message(
+                    create_payout_msg(&held.denom, &held.payee, held.amount)?
+                );
+            }
+            released.push(token_id.clone());
+        }
+
+        if released.is_empty() {
+            return Err(ContractError::NothingToClaim {});
+        }
+
+        Ok(
+            response
+                .add_attribute("action", "release_return_funds")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("token_ids", released.join(", "))
+        )
+    }
+
+    /// Burns a returned token and refunds its held payment to the holder.
+    fn refund_return(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        collection: String,
+        holder: String,
+        token_id: String
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection_info = self.collections.load(deps.storage, collection.clone())?;
+        if collection_info.return_window.is_none() {
+            return Err(ContractError::NotReturnable {});
+        }
+
+        let key = create_mint_log_key(&collection, &token_id);
+        let payment = self.token_payments
+            .may_load(deps.storage, key.clone())?
+            .ok_or(ContractError::NotReturnable {})?;
+
+        if payment.returnable_until < env.block.time.seconds() {
+            return Err(ContractError::NotReturnable {});
+        }
+
+        self.token_payments.remove(deps.storage, key);
+
+        let mut response: Response<EvmMsg> = Response::new().add_message(WasmMsg::Execute {
+            contract_addr: collection.clone(),
+            msg: to_json_binary(&(Cw2981ExecuteMsg::Burn { token_id: token_id.clone() }))?,
+            funds: vec![],
+        });
+
+        for held in payment.held {
+            response = response
+                .add_message(create_payout_msg(&held.denom, &holder, held.amount)?)
+                .add_attribute(format!("refunded_{}", held.denom), held.amount);
+        }
+
+        Ok(
+            response
+                .add_attribute("action", "return_token")
+                .add_attribute("collection", collection)
+                .add_attribute("token_id", token_id)
+                .add_attribute("holder", holder)
+        )
+    }
+
+    pub fn enter_raffle(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: EnterRaffle
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+        let group = collection.mint_groups
+            .iter()
+            .find(|g| g.name == msg.group)
+            .ok_or(ContractError::InvalidMintGroup {})?;
+        let raffle = group.raffle.clone().ok_or(ContractError::InvalidMintGroup {})?;
+
+        let now = env.block.time.seconds();
+        if now < raffle.entry_start || now > raffle.entry_end {
+            return Err(ContractError::RaffleNotOpen {});
+        }
+
+        // The deposit (if any) must be paid exactly
+        match raffle.deposit {
+            Some(deposit) if !deposit.is_zero() => {
+                if
+                    info.funds.len() != 1 ||
+                    info.funds[0].denom != "usei" ||
+                    info.funds[0].amount != deposit
+                {
+                    return Err(ContractError::InvalidFunds {});
+                }
+            }
+            _ => {
+                if !info.funds.is_empty() {
+                    return Err(ContractError::InvalidFunds {});
+                }
+            }
+        }
+
+        let raffle_key = create_raffle_key(&msg.collection, &msg.group);
+        let ticket_key = (raffle_key.clone(), info.sender.to_string());
+        if self.raffle_tickets.has(deps.storage, ticket_key.clone()) {
+            return Err(ContractError::RaffleAlreadyEntered {});
+        }
+
+        let mut state = self.raffles
+            .may_load(deps.storage, raffle_key.clone())?
+            .unwrap_or(RaffleState {
+                entries: 0,
+                winners: 0,
+                seed: None,
+                drawn: None,
+                entropy: None,
+            });
+
+        self.raffle_entries.save(
+            deps.storage,
+            (raffle_key.clone(), state.entries),
+            &info.sender.to_string()
+        )?;
+        self.raffle_tickets.save(
+            deps.storage,
+            ticket_key,
+            &(RaffleTicket {
+                address: info.sender.to_string(),
+                index: state.entries,
+                won: false,
+                minted: false,
+                refunded: false,
+            })
+        )?;
+
+        state.entries += 1;
+        self.raffles.save(deps.storage, raffle_key, &state)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "enter_raffle")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("group", msg.group)
+                .add_attribute("entrant", info.sender)
+        )
+    }
+
+    pub fn draw_raffle(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: DrawRaffle
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        let group = collection.mint_groups
+            .iter()
+            .find(|g| g.name == msg.group)
+            .ok_or(ContractError::InvalidMintGroup {})?;
+        let raffle = group.raffle.clone().ok_or(ContractError::InvalidMintGroup {})?;
+
+        if env.block.time.seconds() <= raffle.entry_end {
+            return Err(ContractError::RaffleNotOpen {});
+        }
+
+        // Past the deadline the raffle is void and the entrants are refunded
+        if env.block.time.seconds() > raffle_draw_deadline(&raffle) {
+            return Err(ContractError::RaffleDrawExpired {});
+        }
+
+        if Keccak256::digest(msg.seed.as_slice()).as_slice() != raffle.seed_commitment.as_slice() {
+            return Err(ContractError::InvalidRandomSeed {});
+        }
+
+        let raffle_key = create_raffle_key(&msg.collection, &msg.group);
+        let mut state = self.raffles
+            .may_load(deps.storage, raffle_key.clone())?
+            .unwrap_or(RaffleState {
+                entries: 0,
+                winners: 0,
+                seed: None,
+                drawn: None,
+                entropy: None,
+            });
+
+        if state.is_drawn() {
+            return Err(ContractError::RaffleAlreadyDrawn {});
+        }
+
+        // The admin starts the draw, once the seed is revealed anyone can continue it
+        let entropy = match state.entropy.clone() {
+            Some(entropy) => entropy,
+            None => {
+                if collection.admin != info.sender {
+                    return Err(ContractError::Unauthorized {});
+                }
+
+                // Entropy for the draw, combining the revealed creator seed with block data
+                let mut hasher = Keccak256::new();
+                hasher.update(msg.seed.as_slice());
+                hasher.update(env.block.height.to_be_bytes());
+                hasher.update(env.block.time.nanos().to_be_bytes());
+                let entropy = Binary::from(hasher.finalize().to_vec());
+
+                state.winners = state.entries.min(group.reserved_supply.u128() as u64);
+                state.seed = Some(msg.seed.clone());
+                state.entropy = Some(entropy.clone());
+                entropy
+            }
+        };
+
+        // Sparse partial Fisher-Yates over the entry indexes, the first `winners` are drawn
+        // and only positions that were swapped are stored
+        let limit = msg.limit.unwrap_or(DEFAULT_DRAW_LIMIT).min(MAX_DRAW_LIMIT) as u64;
+        let drawn = state.drawn.unwrap_or_default();
+        let end = state.winners.min(drawn + limit);
+        for i in drawn..end {
+            let draw = Keccak256::digest([entropy.as_slice(), &i.to_be_bytes()[..]].concat());
+            let offset = u64::from_be_bytes(draw[0..8].try_into().unwrap()) % (state.entries - i);
+
+            let position_key = (raffle_key.clone(), i + offset);
+            let current_key = (raffle_key.clone(), i);
+            let index = self.raffle_draws
+                .may_load(deps.storage, position_key.clone())?
+                .unwrap_or(i + offset);
+            let current = self.raffle_draws
+                .may_load(deps.storage, current_key.clone())?
+                .unwrap_or(i);
+
+            self.raffle_draws.save(deps.storage, position_key, &current)?;
+            self.raffle_draws.remove(deps.storage, current_key);
+
+            let winner = self.raffle_entries.load(deps.storage, (raffle_key.clone(), index))?;
+            let ticket_key = (raffle_key.clone(), winner);
+            let mut ticket = self.raffle_tickets.load(deps.storage, ticket_key.clone())?;
+            ticket.won = true;
+            self.raffle_tickets.save(deps.storage, ticket_key, &ticket)?;
+        }
+
+        state.drawn = Some(end);
+        self.raffles.save(deps.storage, raffle_key, &state)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "draw_raffle")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("group", msg.group)
+                .add_attribute("entries", state.entries.to_string())
+                .add_attribute("winners", state.winners.to_string())
+                .add_attribute("drawn", end.to_string())
+                .add_attribute("random_entropy", HEXLOWER.encode(&entropy))
+        )
+    }
+
+    pub fn claim_raffle_refund(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: ClaimRaffleRefund
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+        let now = env.block.time.seconds();
+        close_open_edition(&mut collection, now);
+
+        let group = collection.mint_groups
+            .iter()
+            .find(|g| g.name == msg.group)
+            .ok_or(ContractError::InvalidMintGroup {})?;
+        let raffle = group.raffle.clone().ok_or(ContractError::InvalidMintGroup {})?;
+
+        let raffle_key = create_raffle_key(&msg.collection, &msg.group);
+        let state = self.raffles.load(deps.storage, raffle_key.clone())?;
+
+        let ticket_key = (raffle_key, info.sender.to_string());
+        let mut ticket = self.raffle_tickets
+            .may_load(deps.storage, ticket_key.clone())?
+            .ok_or(ContractError::NothingToClaim {})?;
+
+        let deposit = raffle.deposit.unwrap_or_default();
+        if ticket.minted || ticket.refunded || deposit.is_zero() {
+            return Err(ContractError::NothingToClaim {});
+        }
+
+        // Once drawn the other entrants are refunded, and so are winners that can no longer
+        // mint: the group ended or the remaining supply can't cover them. A raffle that is
+        // not drawn by the deadline refunds everyone.
+        if state.is_drawn() {
+            if ticket.won {
+                let ended = group.end_time != 0 && now > group.end_time;
+
+                let batch = if collection.collection_type == "404" {
+                    group.batch_size.unwrap()
+                } else {
+                    Uint128::one()
+                };
+                let group_minted = self.global_mint_info
+                    .may_load(
+                        deps.storage,
+                        create_global_mint_info_key(&msg.collection, &group.name)
+                    )?
+                    .unwrap_or_default();
+                let sold_out =
+                    exceeds_supply(&collection, batch) ||
+                    group_minted + Uint128::one() > group.reserved_supply;
+
+                if !ended && !sold_out {
+                    return Err(ContractError::NothingToClaim {});
+                }
+            }
+        } else if now <= raffle_draw_deadline(&raffle) {
+            return Err(ContractError::RaffleNotDrawn {});
+        }
+
+        ticket.refunded = true;
+        self.raffle_tickets.save(deps.storage, ticket_key, &ticket)?;
+
+        Ok(
+            Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(deposit.u128(), "usei"),
+                })
+                .add_attribute("action", "claim_raffle_refund")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("group", msg.group)
+                .add_attribute("recipient", info.sender)
+        )
+    }
+
+    pub fn create_auction(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: CreateAuction
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        if collection.collection_type != "721" {
+            return Err(ContractError::NotImplemented {});
+        }
+
+        // The sequential range of an open edition is only known once it is closed
+        if collection.open_edition.as_ref().is_some_and(|e| !e.closed) {
+            return Err(ContractError::OpenEditionNotEnded {});
+        }
+
+        if msg.end_time <= msg.start_time {
+            return Err(ContractError::Std(StdError::generic_err("End time must be after start")));
+        }
+
+        // One of one tokens live outside of the sequential mint range
+        let start = collection.start_order.unwrap_or_default();
+        let token_key = msg.token_id.to_string();
+        if
+            (msg.token_id >= start && msg.token_id < start + collection.supply) ||
+            self.mint_logs.has(deps.storage, create_mint_log_key(&msg.collection, &token_key))
+        {
+            return Err(ContractError::InvalidTokenId {});
+        }
+
+        let auction_key = (msg.collection.clone(), token_key);
+        if let Some(auction) = self.auctions.may_load(deps.storage, auction_key.clone())? {
+            if auction.status != AuctionStatus::Cancelled {
+                return Err(ContractError::InvalidTokenId {});
+            }
+        }
+
+        let payee = match msg.payee {
+            Some(payee) => deps.api.addr_validate(&payee)?.to_string(),
+            None => collection.admin.to_string(),
+        };
+
+        self.auctioned_ids.save(
+            deps.storage,
+            (msg.collection.clone(), msg.token_id.u128()),
+            &true
+        )?;
+        self.auctions.save(
+            deps.storage,
+            auction_key,
+            &(Auction {
+                token_id: msg.token_id,
+                payee,
+                start_time: msg.start_time,
+                end_time: msg.end_time,
+                reserve_price: msg.reserve_price,
+                min_increment: msg.min_increment.unwrap_or(Uint128::one()),
+                extension_window: msg.extension_window.unwrap_or_default(),
+                highest_bid: None,
+                status: AuctionStatus::Active,
+            })
+        )?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "create_auction")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("token_id", msg.token_id.to_string())
+                .add_attribute("reserve_price", msg.reserve_price.to_string())
+        )
+    }
+
+    pub fn place_bid(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: PlaceBid
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.paused_at.is_some() {
+            return Err(ContractError::CollectionPaused {});
+        }
+
+        if collection.escrow == Some(EscrowStatus::Cancelled) {
+            return Err(ContractError::CollectionCancelled {});
+        }
+
+        let auction_key = (msg.collection.clone(), msg.token_id.to_string());
+        let mut auction = self.auctions.load(deps.storage, auction_key.clone())?;
+
+        let now = env.block.time.seconds();
+        if
+            auction.status != AuctionStatus::Active ||
+            now < auction.start_time ||
+            now >= auction.end_time
+        {
+            return Err(ContractError::AuctionNotActive {});
+        }
+
+        if info.funds.len() != 1 || info.funds[0].denom != "usei" {
+            return Err(ContractError::InvalidFunds {});
+        }
+
+        // The bid has to clear the reserve, the platform fee and the previous bid plus increment
+        let amount = info.funds[0].amount;
+        let min_bid = match &auction.highest_bid {
+            Some(bid) => bid.amount + auction.min_increment,
+            None => auction.reserve_price.max(config.fee),
+        };
+        if amount < min_bid {
+            return Err(ContractError::BidTooLow {});
+        }
+
+        let recipient = msg.recipient.unwrap_or(info.sender.to_string());
+        let recipient = self.resolve_recipient(&deps, &collection.chain, &recipient)?;
+
+        let mut response: Response<EvmMsg> = Response::new();
+
+        // Refund the outbid bidder
+        if let Some(previous) = auction.highest_bid.take() {
+            response = response.add_message(BankMsg::Send {
+                to_address: previous.bidder.to_string(),
+                amount: coins(previous.amount.u128(), "usei"),
+            });
+        }
+
+        // Bids landing close to the end extend the auction
+        if auction.end_time - now < auction.extension_window {
+            auction.end_time = now + auction.extension_window;
+        }
+
+        auction.highest_bid = Some(Bid {
+            bidder: info.sender.clone(),
+            recipient,
+            amount,
+        });
+        self.auctions.save(deps.storage, auction_key, &auction)?;
+
+        Ok(
+            response
+                .add_attribute("action", "place_bid")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("token_id", msg.token_id.to_string())
+                .add_attribute("bidder", info.sender)
+                .add_attribute("amount", amount.to_string())
+                .add_attribute("end_time", auction.end_time.to_string())
+        )
+    }
+
+    pub fn settle_auction(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        _info: MessageInfo,
+        msg: SettleAuction
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        let token_key = msg.token_id.to_string();
+        let auction_key = (msg.collection.clone(), token_key.clone());
+        let mut auction = self.auctions.load(deps.storage, auction_key.clone())?;
+
+        if auction.status != AuctionStatus::Active {
+            return Err(ContractError::AuctionNotActive {});
+        }
+
+        if env.block.time.seconds() < auction.end_time {
+            return Err(ContractError::AuctionNotEnded {});
+        }
+
+        auction.status = AuctionStatus::Settled;
+        self.auctions.save(deps.storage, auction_key, &auction)?;
+
+        let mut response: Response<EvmMsg> = Response::new();
+
+        let bid = match auction.highest_bid {
+            Some(bid) => bid,
+            None => {
+                return Ok(
+                    response
+                        .add_attribute("action", "settle_auction")
+                        .add_attribute("collection", msg.collection)
+                        .add_attribute("token_id", token_key)
+                        .add_attribute("sold", "false")
+                );
+            }
+        };
+
+        // A cancelled collection can not sell anymore, the winning bid goes back
+        if collection.escrow == Some(EscrowStatus::Cancelled) {
+            return Ok(
+                response
+                    .add_message(BankMsg::Send {
+                        to_address: bid.bidder.to_string(),
+                        amount: coins(bid.amount.u128(), "usei"),
+                    })
+                    .add_attribute("action", "settle_auction")
+                    .add_attribute("collection", msg.collection)
+                    .add_attribute("token_id", token_key)
+                    .add_attribute("sold", "false")
+            );
+        }
+
+        // Platform fee and partner split, as for a single mint
+        if config.fee > Uint128::zero() {
+            response = response.add_messages(
+                self.create_fee_msgs(
+                    deps.storage,
+                    &collection,
+                    &msg.collection,
+                    bid.bidder.as_ref(),
+                    config.fee,
+                    Uint128::one()
+                )?
+            );
+        }
+
+        let proceeds = bid.amount.checked_sub(config.fee).map_err(StdError::overflow)?;
+        if
+            let Some(payout) = self.pay_proceeds(
+                deps.storage,
+                &collection,
+                &msg.collection,
+                bid.bidder.as_ref(),
+                &auction.payee,
+                proceeds
+            )?
+        {
+            response = response.add_message(payout);
+        }
+
+        // Mint through the regular 721 path
+        response = response.add_messages(
+            self.create_721_mint_msgs(&collection, &bid.recipient, &[msg.token_id.u128()])?
+        );
+
+        let mint_log_key = create_mint_log_key(&msg.collection, &token_key);
+        self.mint_logs.save(deps.storage, mint_log_key.clone(), &bid.bidder.to_string())?;
+        self.recipient_logs.save(deps.storage, mint_log_key, &bid.recipient)?;
+
+        Ok(
+            response
+                .add_attribute("action", "settle_auction")
+                .add_attribute("chain", collection.chain)
+                .add_attribute("collection", msg.collection)
+                .add_attribute("token_id", token_key)
+                .add_attribute("sold", "true")
+                .add_attribute("winner", bid.bidder)
+                .add_attribute("recipient", bid.recipient)
+                .add_attribute("amount", bid.amount.to_string())
+        )
+    }
+
+    pub fn cancel_auction(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        _env: Env,
+        info: MessageInfo,
+        msg: CancelAuction
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.admin != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let auction_key = (msg.collection.clone(), msg.token_id.to_string());
+        let mut auction = self.auctions.load(deps.storage, auction_key.clone())?;
+
+        if auction.status != AuctionStatus::Active {
+            return Err(ContractError::AuctionNotActive {});
+        }
+
+        // Bidders are owed the token once a bid lands
+        if auction.highest_bid.is_some() {
+            return Err(ContractError::AuctionHasBids {});
+        }
+
+        auction.status = AuctionStatus::Cancelled;
+        self.auctions.save(deps.storage, auction_key, &auction)?;
+        self.auctioned_ids.remove(deps.storage, (msg.collection.clone(), msg.token_id.u128()));
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "cancel_auction")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("token_id", msg.token_id.to_string())
+        )
+    }
+
+    pub fn reserve(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: Reserve
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let config = self.config.load(deps.storage)?;
+        let collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        if collection.paused_at.is_some() {
+            return Err(ContractError::CollectionPaused {});
+        }
+
+        if collection.escrow == Some(EscrowStatus::Cancelled) {
+            return Err(ContractError::CollectionCancelled {});
+        }
+
+        let group = collection.mint_groups
+            .iter()
+            .find(|g| g.name == msg.group)
+            .ok_or(ContractError::InvalidMintGroup {})?;
+        let presale = group.presale.clone().ok_or(ContractError::InvalidMintGroup {})?;
+
+        let now = env.block.time.seconds();
+        if now < presale.start_time || now >= group.start_time {
+            return Err(ContractError::PresaleNotOpen {});
+        }
+
+        if msg.amount.is_zero() {
+            return Err(ContractError::InvalidFunds {});
+        }
+
+        // The buyer pays and claims, eligibility and limits follow the minter as in Mint
+        let buyer = info.sender.to_string();
+        let minter = self.resolve_minter(
+            &deps,
+            &env,
+            &info.sender,
+            &msg.collection,
+            msg.vault.clone(),
+            msg.evm_address_proof.clone()
+        )?;
+
+        let group_key = create_group_key(&buyer, &msg.collection, &group.name);
+        let mut reservation = self.reservations
+            .may_load(deps.storage, group_key.clone())?
+            .unwrap_or(Reservation {
+                amount: Uint128::zero(),
+                fee: Uint128::zero(),
+                payouts: Vec::new(),
+                limit_owner: Some(minter.limit_owner.clone()),
+            });
+
+        // Later reservations add to the first one, so they must count under the same key
+        let limit_owner = reservation.limit_owner.clone().unwrap_or(buyer.clone());
+        if limit_owner != minter.limit_owner {
+            return Err(
+                ContractError::Std(
+                    StdError::generic_err("Reservation counts under another address")
+                )
+            );
+        }
+
+        let limit_key = create_group_key(&limit_owner, &msg.collection, &group.name);
+        let mint_info = self.mint_info
+            .may_load(deps.storage, limit_key.clone())?
+            .unwrap_or(MintInfo {
+                mints: Vec::new(),
+                editions: None,
+            });
+        let reserved = self.wallet_reserved
+            .may_load(deps.storage, limit_key.clone())?
+            .unwrap_or_default();
+
+        // Validate merkle proof or allowlist (if any)
+        let allocation = self.check_eligibility(
+            deps.storage,
+            &msg.collection,
+            group,
+            &minter,
+            msg.merkle_proof.clone(),
+            msg.merkle_proof_address_type.clone()
+        )?;
+        if let Some(allocation) = allocation {
+            if mint_info.count() + reserved.u128() + msg.amount.u128() > allocation.u128() {
+                return Err(ContractError::MaxTokensMinted {});
+            }
+        }
+
+        // Check if the minter already reserved or minted the max tokens
+        if
+            group.max_mints_per_wallet.u128() != 0 &&
+            mint_info.count() + reserved.u128() + msg.amount.u128() >
+                group.max_mints_per_wallet.u128()
+        {
+            return Err(ContractError::MaxTokensMinted {});
+        }
+
+        // Check if the reserved supply ran out for the group
+        let global_mint_info_key = create_global_mint_info_key(&msg.collection, &group.name);
+        let global_mint_info = self.global_mint_info
+            .may_load(deps.storage, global_mint_info_key.clone())?
+            .unwrap_or_default();
+        let reserved_total = self.reserved_totals
+            .may_load(deps.storage, global_mint_info_key.clone())?
+            .unwrap_or_default();
+        if
+            group.reserved_supply != Uint128::zero() &&
+            global_mint_info + reserved_total + msg.amount > group.reserved_supply
+        {
+            return Err(ContractError::ReservedSupplyRanOut {});
+        }
+
+        // The price is locked in at the first reservation
+        if reservation.amount.is_zero() {
+            reservation.payouts = group.payments
+                .iter()
+                .map(|p| HeldPayment {
+                    payee: p.args[0].clone(),
+                    denom: "usei".to_string(),
+                    amount: p.amount.unwrap(),
+                })
+                .collect();
+            reservation.fee = if reservation.payouts.is_empty() {
+                Uint128::zero()
+            } else {
+                config.fee
+            };
+        }
+
+        // The buyer pays the full price (and fee) now
+        let price = reservation.payouts
+            .iter()
+            .fold(reservation.fee, |acc, p| acc + p.amount);
+        let expected = price * msg.amount;
+        if expected.is_zero() {
+            if !info.funds.is_empty() {
+                return Err(ContractError::InvalidFunds {});
+            }
+        } else if
+            info.funds.len() != 1 ||
+            info.funds[0].denom != "usei" ||
+            info.funds[0].amount != expected
+        {
+            return Err(ContractError::InvalidFunds {});
+        }
+
+        reservation.amount += msg.amount;
+        self.reservations.save(deps.storage, group_key, &reservation)?;
+        self.reserved_totals.save(
+            deps.storage,
+            global_mint_info_key,
+            &(reserved_total + msg.amount)
+        )?;
+        self.wallet_reserved.save(deps.storage, limit_key, &(reserved + msg.amount))?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "reserve")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("group", msg.group)
+                .add_attribute("buyer", buyer)
+                .add_attribute("amount", msg.amount.to_string())
+                .add_attribute("paid_sei", expected.to_string())
+        )
+    }
+
+    pub fn claim(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: Claim
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        let buyer = info.sender.to_string();
+        let group_key = create_group_key(&buyer, &msg.collection, &msg.group);
+        let reservation = self.reservations
+            .may_load(deps.storage, group_key.clone())?
+            .ok_or(ContractError::NothingToClaim {})?;
+
+        let group = match collection.mint_groups.iter().find(|g| g.name == msg.group) {
+            Some(group) if group.presale.is_some() => group.clone(),
+            // The group or its presale was removed after the reservation, refund it in full
+            _ => {
+                return self.refund_reservation(deps, msg, buyer, reservation);
+            }
+        };
+        let presale = group.presale.clone().unwrap();
+
+        let cancelled = collection.escrow == Some(EscrowStatus::Cancelled);
+        close_open_edition(&mut collection, env.block.time.seconds());
+
+        // Claims open with the mint group, or with the reveal if so configured
+        if !cancelled {
+            if collection.paused_at.is_some() {
+                return Err(ContractError::CollectionPaused {});
+            }
+            if env.block.time.seconds() < group.start_time {
+                return Err(ContractError::GroupNotOpenToMint {});
+            }
+            if presale.claim_on_reveal == Some(true) && collection.hidden_metadata {
+                return Err(ContractError::GroupNotOpenToMint {});
+            }
+        }
+
+        let limit_key = self.release_reservation(
+            deps.storage,
+            &msg.collection,
+            &msg.group,
+            &buyer,
+            &reservation
+        )?;
+        let global_mint_info_key = create_global_mint_info_key(&msg.collection, &group.name);
+
+        // Mint as much of the reservation as the supply still allows
+        let batch = if collection.collection_type == "404" {
+            group.batch_size.unwrap()
+        } else {
+            Uint128::one()
+        };
+        let mut amount = if cancelled { Uint128::zero() } else { reservation.amount };
+        while !amount.is_zero() && exceeds_supply(&collection, amount * batch) {
+            amount -= Uint128::one();
+        }
+
+        let recipient = if amount.is_zero() {
+            buyer.clone()
+        } else {
+            self.resolve_recipient(&deps, &collection.chain, &buyer)?
+        };
+        let mut response: Response<EvmMsg> = Response::new();
+        let mut attrs: Vec<Attribute> = Vec::new();
+
+        // Refund the part of the reservation that can not be filled
+        let price = reservation.payouts
+            .iter()
+            .fold(reservation.fee, |acc, p| acc + p.amount);
+        let refund = price * (reservation.amount - amount);
+        if !refund.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: buyer.clone(),
+                amount: coins(refund.u128(), "usei"),
+            });
+        }
+
+        if !amount.is_zero() {
+            for payout in reservation.payouts.iter() {
+                if
+                    let Some(msg) = self.pay_proceeds(
+                        deps.storage,
+                        &collection,
+                        &msg.collection,
+                        &buyer,
+                        &payout.payee,
+                        payout.amount * amount
+                    )?
+                {
+                    response = response.add_message(msg);
+                }
+            }
+
+            if !reservation.fee.is_zero() {
+                response = response.add_messages(
+                    self.create_fee_msgs(
+                        deps.storage,
+                        &collection,
+                        &msg.collection,
+                        &buyer,
+                        reservation.fee,
+                        amount
+                    )?
+                );
+            }
+
+            let mut mint_info = self.mint_info
+                .may_load(deps.storage, limit_key.clone())?
+                .unwrap_or(MintInfo {
+                    mints: Vec::new(),
+                    editions: None,
+                });
+            let mut global_mint_info = self.global_mint_info
+                .may_load(deps.storage, global_mint_info_key.clone())?
+                .unwrap_or_default();
+
+            if collection.collection_type == "721" {
+                let token_ids = self.next_token_ids(
+                    deps.storage,
+                    &env,
+                    &collection,
+                    &buyer,
+                    amount,
+                    &mut attrs
+                )?;
+                response = response.add_messages(
+                    self.create_721_mint_msgs(&collection, &recipient, &token_ids)?
+                );
+
+                for token_id in token_ids.iter() {
+                    let mint_log_key = create_mint_log_key(&msg.collection, &token_id.to_string());
+                    self.mint_logs.save(deps.storage, mint_log_key.clone(), &buyer)?;
+                    self.recipient_logs.save(deps.storage, mint_log_key, &recipient)?;
+                    mint_info.mints.push(Uint128::from(*token_id));
+                }
+
+                attrs.push(Attribute {
+                    key: "token_ids".to_string(),
+                    value: token_ids
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                });
+            } else if collection.collection_type == "1155" {
+                let edition_id = group.edition_id.unwrap();
+                response = response.add_message(
+                    self.create_1155_mint_msg(&collection, &recipient, edition_id, amount)?
+                );
+
+                let mint_log_key = create_mint_log_key_404(
+                    &msg.collection,
+                    &group.name,
+                    &global_mint_info.to_string()
+                );
+                self.mint_logs.save(deps.storage, mint_log_key.clone(), &buyer)?;
+                self.recipient_logs.save(deps.storage, mint_log_key, &recipient)?;
+
+                mint_info.add_edition(edition_id, amount);
+            } else {
+                let callback = self.create_404_mint_msg(&collection, &recipient, batch)?;
+
+                for i in 0..amount.u128() {
+                    response = response.add_message(callback.clone());
+
+                    let mint_log_key = create_mint_log_key_404(
+                        &msg.collection,
+                        &group.name,
+                        &(global_mint_info.u128() + i).to_string()
+                    );
+                    self.mint_logs.save(deps.storage, mint_log_key.clone(), &buyer)?;
+                    self.recipient_logs.save(deps.storage, mint_log_key, &recipient)?;
+                    mint_info.mints.push(batch);
+                }
+            }
+
+            self.mint_info.save(deps.storage, limit_key, &mint_info)?;
+
+            global_mint_info += amount;
+            self.global_mint_info.save(deps.storage, global_mint_info_key, &global_mint_info)?;
+
+            collection.next_token += batch * amount;
+
+            if exceeds_supply(&collection, Uint128::one()) {
+                self.reach_milestone(&mut collection, Milestone::SoldOut);
+            }
+
+            self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+        }
+
+        Ok(
+            response
+                .add_attribute("action", "claim")
+                .add_attribute("chain", collection.chain)
+                .add_attribute("collection", msg.collection)
+                .add_attribute("group", msg.group)
+                .add_attribute("recipient", recipient)
+                .add_attribute("mint_amount", amount.to_string())
+                .add_attribute("refunded_sei", refund.to_string())
+                .add_attributes(attrs)
+        )
+    }
+
+    pub fn close_open_edition(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: Env,
+        info: MessageInfo,
+        msg: CloseOpenEdition
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let mut collection = self.collections.load(deps.storage, msg.collection.clone())?;
+
+        // Anyone can close an open edition once its window ended
+        if !close_open_edition(&mut collection, env.block.time.seconds()) {
+            return Err(ContractError::OpenEditionNotEnded {});
+        }
+
+        self.reach_milestone(&mut collection, Milestone::SoldOut);
+        self.collections.save(deps.storage, msg.collection.clone(), &collection)?;
+
+        Ok(
+            Response::new()
+                .add_attribute("action", "close_open_edition")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("supply", collection.supply.to_string())
+                .add_attribute("sender", info.sender)
+        )
+    }
+
+    /// Instantiates a v1 collection contract through a submessage, registered on reply.
+    /// With a salt the contract is instantiated with Instantiate2 and, its address being
+    /// known up front, the collection is registered right away.
+    fn submit_instantiate(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        env: &Env,
+        config: &mut Config,
+        mut collection: Collection,
+        instantiate: WasmMsg,
+        salt: Option<Binary>
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        let wasm_msg = match (instantiate, salt) {
+            (WasmMsg::Instantiate { admin, code_id, msg, funds, label }, Some(salt)) => {
+                let checksum = deps.querier.query_wasm_code_info(code_id)?.checksum;
+                let creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+                let address = instantiate2_address(&checksum, &creator, &salt).map_err(|e|
+                    StdError::generic_err(e.to_string())
+                )?;
+                let address = deps.api.addr_humanize(&address)?.to_string();
+
+                if self.collections.has(deps.storage, address.clone()) {
+                    return Err(ContractError::CollectionExists {});
+                }
+
+                collection.collection_address = Some(address.clone());
+                self.collections.save(deps.storage, address, &collection)?;
+
+                WasmMsg::Instantiate2 { admin, code_id, label, msg, funds, salt }
+            }
+            (instantiate, _) => instantiate,
+        };
+
+        self.instantiates.save(deps.storage, config.next_reply_id, &collection)?;
+
+        let sub_msg: Vec<SubMsg<EvmMsg>> = vec![SubMsg {
+            msg: wasm_msg.into(),
+            id: config.next_reply_id,
+            gas_limit: None,
+            reply_on: ReplyOn::Always,
+        }];
+
+        config.next_reply_id += 1;
+        self.config.save(deps.storage, config)?;
+
+        let mut response = Response::<EvmMsg>::new().add_submessages(sub_msg);
+        if let Some(address) = collection.collection_address {
+            response = response.add_attribute("collection", address);
+        }
+
+        Ok(response)
+    }
+
+    /// Returns the code id to instantiate, which must be approved for the kind.
+    /// Without a code id the default approved code is used.
+    fn resolve_code_id(
+        &self,
+        storage: &dyn Storage,
+        kind: &str,
+        code_id: Option<u64>
+    ) -> Result<u64, ContractError> {
+        match code_id {
+            Some(code_id) => {
+                if !self.approved_codes.has(storage, (kind.to_string(), code_id)) {
+                    return Err(ContractError::CodeNotApproved {});
+                }
+                Ok(code_id)
+            }
+            None =>
+                self.approved_codes
+                    .prefix(kind.to_string())
+                    .range(storage, None, None, Order::Ascending)
+                    .filter_map(|item| item.ok())
+                    .find(|(_, code)| code.default)
+                    .map(|(code_id, _)| code_id)
+                    .ok_or(ContractError::CodeNotApproved {}),
+        }
+    }
+
+    /// Refunds a reservation in full and releases its share of the reserved supply.
+    fn refund_reservation(
+        &self,
+        deps: DepsMut<EvmQueryWrapper>,
+        msg: Claim,
+        buyer: String,
+        reservation: Reservation
+    ) -> Result<Response<EvmMsg>, ContractError> {
+        self.release_reservation(deps.storage, &msg.collection, &msg.group, &buyer, &reservation)?;
+
+        let price = reservation.payouts
+            .iter()
+            .fold(reservation.fee, |acc, p| acc + p.amount);
+        let refund = price * reservation.amount;
+
+        let mut response: Response<EvmMsg> = Response::new();
+        if !refund.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: buyer.clone(),
+                amount: coins(refund.u128(), "usei"),
+            });
+        }
+
+        Ok(
+            response
+                .add_attribute("action", "claim")
+                .add_attribute("collection", msg.collection)
+                .add_attribute("group", msg.group)
+                .add_attribute("recipient", buyer)
+                .add_attribute("mint_amount", "0")
+                .add_attribute("refunded_sei", refund.to_string())
+        )
+    }
+
+    /// Removes a claimed or refunded reservation from the group and wallet totals.
+    /// Returns the key the minter's limits count under.
+    fn release_reservation(
+        &self,
+        storage: &mut dyn Storage,
+        collection_key: &str,
+        group_name: &str,
+        buyer: &str,
+        reservation: &Reservation
+    ) -> StdResult<String> {
+        self.reservations.remove(storage, create_group_key(buyer, collection_key, group_name));
+
+        let global_mint_info_key = create_global_mint_info_key(collection_key, group_name);
+        let reserved_total = self.reserved_totals
+            .may_load(storage, global_mint_info_key.clone())?
+            .unwrap_or_default();
+        self.reserved_totals.save(
+            storage,
+            global_mint_info_key,
+            &reserved_total.saturating_sub(reservation.amount)
+        )?;
+
+        let limit_owner = reservation.limit_owner.as_deref().unwrap_or(buyer);
+        let limit_key = create_group_key(limit_owner, collection_key, group_name);
+        let reserved = self.wallet_reserved
+            .may_load(storage, limit_key.clone())?
+            .unwrap_or_default();
+        self.wallet_reserved.save(
+            storage,
+            limit_key.clone(),
+            &reserved.saturating_sub(reservation.amount)
+        )?;
+
+        Ok(limit_key)
+    }
+
+    /// Resolves the minter for a mint or reservation sent by `sender`: the vault it mints for
+    /// (if delegated), the evm address the minter controls (proven by signature or by
+    /// association) and the key its limits count under.
+    fn resolve_minter(
+        &self,
+        deps: &DepsMut<EvmQueryWrapper>,
+        env: &Env,
+        sender: &Addr,
+        collection_key: &str,
+        vault: Option<String>,
+        evm_address_proof: Option<EvmAddressProof>
+    ) -> Result<Minter, ContractError> {
+        let mut minter = sender.clone();
+
+        if let Some(vault) = vault {
+            let vault = deps.api.addr_validate(&vault)?;
+            let collection_key = create_delegation_key(sender.as_ref(), Some(collection_key));
+            let global_key = create_delegation_key(sender.as_ref(), None);
+            if
+                !self.delegations.has(deps.storage, (vault.to_string(), collection_key)) &&
+                !self.delegations.has(deps.storage, (vault.to_string(), global_key))
+            {
+                return Err(ContractError::NotDelegated {});
+            }
+            minter = vault;
+        }
+
+        let evm_address = match evm_address_proof {
+            Some(proof) => {
+                let message = format!(
+                    "{}|{}|{}",
+                    env.contract.address,
+                    env.block.chain_id,
+                    minter
+                );
+                let signer = recover_evm_address(
+                    deps.api,
+                    message.as_bytes(),
+                    &proof.signature
+                ).map_err(|_| ContractError::InvalidEvmSignature {})?;
+                if signer != proof.address.to_lowercase() {
+                    return Err(ContractError::InvalidEvmSignature {});
+                }
+                Some(signer)
+            }
+            None => {
+                let querier = EvmQuerier::new(&deps.querier);
+                let evm_address_query = querier.query_evm_address(minter.to_string())?;
+                if evm_address_query.associated {
+                    Some(evm_address_query.evm_address.to_lowercase())
+                } else {
+                    None
+                }
+            }
+        };
+
+        // Limits follow the proven or associated evm address, so one key can't claim
+        // a fresh allocation for every wallet it signs for, or once with and once
+        // without a proof
+        let limit_owner = evm_address.clone().unwrap_or(minter.to_string());
+
+        Ok(Minter {
+            address: minter,
+            evm_address,
+            limit_owner,
+        })
+    }
+
+    /// Checks the minter against the group's merkle root or on-chain allowlist.
+    /// Returns the minter's allowlist allocation, if it has one.
+    fn check_eligibility(
+        &self,
+        storage: &dyn Storage,
+        collection_key: &str,
+        group: &MintGroup,
+        minter: &Minter,
+        merkle_proof: Option<Vec<Vec<u8>>>,
+        merkle_proof_address_type: Option<String>
+    ) -> Result<Option<Uint128>, ContractError> {
+        if let Some(merkle_root) = group.merkle_root.clone() {
+            let proof = merkle_proof.ok_or(ContractError::InvalidMerkleProof {})?;
+
+            let leaf_address = if merkle_proof_address_type.as_deref() == Some("hex") {
+                minter.evm_address.clone().ok_or(ContractError::NotAssociatedAddress {})?
+            } else {
+                minter.address.to_string()
+            };
+
+            // Check that the merkle proof and root is valid
+            let leaf = Keccak256::digest(leaf_address.as_bytes()).to_vec();
+            if !validate_merkle_proof(proof, merkle_root, leaf) {
+                return Err(ContractError::InvalidMerkleProof {});
+            }
+        } else if group.allowlist == Some(true) {
+            // Check the on-chain allowlist, by bech32 address first and then by hex address
+            let bech32_key = (
+                collection_key.to_string(),
+                group.name.clone(),
+                minter.address.to_string(),
+            );
+            let bech32_entry = self.allowlists.may_load(storage, bech32_key)?;
+            let entry = match (bech32_entry, &minter.evm_address) {
+                (Some(entry), _) => Some(entry),
+                (None, Some(evm_address)) => {
+                    let hex_key = (
+                        collection_key.to_string(),
+                        group.name.clone(),
+                        evm_address.clone(),
+                    );
+                    self.allowlists.may_load(storage, hex_key)?
+                }
+                (None, None) => None,
+            };
+
+            let entry = entry.ok_or(ContractError::NotOnAllowlist {})?;
+            return Ok(entry.allocation);
+        }
+
+        Ok(None)
+    }
+
+    /// Pays usei proceeds to the payee, holding the share the collection's escrow or vesting
+    /// setup keeps back. Returns the message paying out the direct part (if any).
+    fn pay_proceeds(
+        &self,
+        storage: &mut dyn Storage,
+        collection: &Collection,
+        collection_key: &str,
+        payer: &str,
+        payee: &str,
+        amount: Uint128
+    ) -> Result<Option<CosmosMsg<EvmMsg>>, ContractError> {
+        let escrow = collection.escrow == Some(EscrowStatus::Active);
+        let held = match (escrow, &collection.vesting) {
+            (true, _) => amount,
+            (false, Some(vesting)) => amount.multiply_ratio(vesting.held_percent, 100u128),
+            (false, None) => Uint128::zero(),
+        };
+
+        if !held.is_zero() {
+            if escrow {
+                self.hold_payment(storage, collection_key, payer, payee, "usei", held)?;
+            } else {
+                self.hold_vesting(storage, collection_key, payee, "usei", held)?;
+            }
+        }
+
+        if (amount - held).is_zero() {
+            return Ok(None);
+        }
+
+        Ok(
+            Some(
+                (BankMsg::Send {
+                    to_address: payee.to_string(),
+                    amount: coins((amount - held).u128(), "usei"),
+                }).into()
+            )
+        )
+    }
+
+    /// Records a share of the proceeds that vests to the payee.
+    fn hold_vesting(
+        &self,
+        storage: &mut dyn Storage,
+        collection: &str,
+        payee: &str,
+        denom: &str,
+        amount: Uint128
+    ) -> Result<(), ContractError> {
+        let key = (collection.to_string(), payee.to_string(), denom.to_string());
+        let mut balance = self.vesting_balances
+            .may_load(storage, key.clone())?
+            .unwrap_or(VestingBalance {
+                total: Uint128::zero(),
+                claimed: Uint128::zero(),
+            });
+        balance.total += amount;
+        self.vesting_balances.save(storage, key, &balance)?;
+
+        Ok(())
+    }
+
+    /// Marks a vesting milestone of the collection as reached.
+    fn reach_milestone(&self, collection: &mut Collection, milestone: Milestone) {
+        if collection.vesting.is_none() {
+            return;
+        }
+
+        let mut reached = collection.reached_milestones.clone().unwrap_or_default();
+        if !reached.contains(&milestone) {
+            reached.push(milestone);
+            collection.reached_milestones = Some(reached);
+        }
+    }
+
+    /// Records a payment held in escrow, for the payee's release and the payer's refund.
+    fn hold_payment(
+        &self,
+        storage: &mut dyn Storage,
+        collection: &str,
+        payer: &str,
+        payee: &str,
+        denom: &str,
+        amount: Uint128
+    ) -> Result<(), ContractError> {
+        let balance_key = (collection.to_string(), payee.to_string(), denom.to_string());
+        let balance = self.escrow_balances
+            .may_load(storage, balance_key.clone())?
+            .unwrap_or_default();
+        self.escrow_balances.save(storage, balance_key, &(balance + amount))?;
+
+        let payment_key = (collection.to_string(), payer.to_string(), denom.to_string());
+        let paid = self.escrow_payments
+            .may_load(storage, payment_key.clone())?
+            .unwrap_or_default();
+        self.escrow_payments.save(storage, payment_key, &(paid + amount))?;
+
+        Ok(())
+    }
+
+    /// Assigns the next `amount` token ids, in order or drawn at random from the remaining pool.
+    fn next_token_ids(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        collection: &Collection,
+        minter: &str,
+        amount: Uint128,
+        attrs: &mut Vec<Attribute>
+    ) -> Result<Vec<u128>, ContractError> {
+        let start_order = collection.start_order.unwrap_or(Uint128::zero()).u128();
+        let next_token = collection.next_token.u128();
+        let collection_address = collection.collection_address.clone().unwrap();
+
+        if collection.random_seed_commitment.is_none() {
+            let token_ids: Vec<u128> = (next_token..next_token + amount.u128()).collect();
+            for token_id in token_ids.iter() {
+                if self.auctioned_ids.has(storage, (collection_address.clone(), *token_id)) {
+                    return Err(ContractError::InvalidTokenId {});
+                }
+            }
+            return Ok(token_ids);
+        }
+
+        let seed = collection.random_seed.clone().ok_or(ContractError::RandomSeedNotRevealed {})?;
+        let minted = next_token - start_order;
+
+        // Entropy for this mint, combining the revealed creator seed with block data
+        let mut hasher = Keccak256::new();
+        hasher.update(seed.as_slice());
+        hasher.update(env.block.height.to_be_bytes());
+        hasher.update(env.block.time.nanos().to_be_bytes());
+        hasher.update(env.transaction.as_ref().map(|t| t.index).unwrap_or(0).to_be_bytes());
+        hasher.update(minter.as_bytes());
+        hasher.update(minted.to_be_bytes());
+        let entropy = hasher.finalize();
+
+        attrs.push(Attribute {
+            key: "random_entropy".to_string(),
+            value: HEXLOWER.encode(&entropy),
+        });
+
+        // Sparse Fisher-Yates: positions [0, remaining) hold the unassigned ids,
+        // and only positions that were swapped are stored.
+        let mut remaining = collection.supply.u128() - minted;
+        let mut token_ids = Vec::new();
+        for i in 0..amount.u128() {
+            let draw = Keccak256::digest([&entropy[..], &i.to_be_bytes()[..]].concat());
+            let position = u128::from_be_bytes(draw[0..16].try_into().unwrap()) % remaining;
+            let last = remaining - 1;
+
+            let position_key = create_random_pool_key(&collection_address, position);
+            let last_key = create_random_pool_key(&collection_address, last);
+            let id = self.random_pool.may_load(storage, position_key.clone())?.unwrap_or(position);
+            let last_id = self.random_pool.may_load(storage, last_key.clone())?.unwrap_or(last);
+
+            self.random_pool.save(storage, position_key, &last_id)?;
+            self.random_pool.remove(storage, last_key);
+
+            let token_id = start_order + id;
+            if self.auctioned_ids.has(storage, (collection_address.clone(), token_id)) {
+                return Err(ContractError::InvalidTokenId {});
+            }
+
+            token_ids.push(token_id);
+            remaining -= 1;
+        }
+
+        Ok(token_ids)
+    }
+
+    /// Checks if any one of one auction id falls in [start, end).
+    fn covers_auctioned_id(
+        &self,
+        storage: &dyn Storage,
+        collection_key: &str,
+        start: Uint128,
+        end: Uint128
+    ) -> StdResult<bool> {
+        let mut ids = self.auctioned_ids
+            .prefix(collection_key.to_string())
+            .keys(
+                storage,
+                Some(Bound::inclusive(start.u128())),
+                Some(Bound::exclusive(end.u128())),
+                Order::Ascending
+            );
+        Ok(ids.next().transpose()?.is_some())
+    }
+
+    /// Resolves a bech32 or hex recipient to the address format of the collection's chain.
+    fn resolve_recipient(
+        &self,
+        deps: &DepsMut<EvmQueryWrapper>,
+        chain: &str,
+        address: &str
+    ) -> Result<String, ContractError> {
+        let querier = EvmQuerier::new(&deps.querier);
+
+        if chain == "v2" {
+            if address.starts_with("0x") {
+                pad_address_to_bytes32(address)?; // validate hex
+                return Ok(address.to_lowercase());
+            }
+
+            let evm_address_query = querier.query_evm_address(
+                deps.api.addr_validate(address)?.to_string()
+            )?;
+            if !evm_address_query.associated {
+                return Err(ContractError::NotAssociatedAddress {});
+            }
+            return Ok(evm_address_query.evm_address);
+        }
+
+        let recipient = normalize_address(deps, address)?;
+        if recipient.starts_with("0x") {
+            return Err(ContractError::NotAssociatedAddress {});
+        }
+
+        Ok(recipient)
+    }
+
+    /// Builds the messages sending the platform fee (per token) for `amount` tokens to the
+    /// config admin, with the partner's share (if any) split off. In escrow mode the fee is
+    /// held with the proceeds, so it is released on finalize and refunded on cancel.
+    fn create_fee_msgs(
+        &self,
+        storage: &mut dyn Storage,
+        collection: &Collection,
+        collection_key: &str,
+        payer: &str,
+        fee: Uint128,
+        amount: Uint128
+    ) -> Result<Vec<CosmosMsg<EvmMsg>>, ContractError> {
+        let mut shares: Vec<(String, u128)> = Vec::new();
+        let mut admin_fee = fee.u128() * amount.u128();
+
+        // Transfer the admin fee to the collection admin and partner (if any)
+        if collection.partner.is_some() {
+            let partner = self.partners.may_load(
+                storage,
+                collection.partner.clone().unwrap().to_string()
+            )?;
+            if let Some(partner_data) = partner {
+                if partner_data.fee_percent > Uint128::zero() {
+                    let partner_fee =
+                        (fee.u128() * partner_data.fee_percent.u128()) / 100; //calculate partner fee
+                    shares.push((partner_data.partner.to_string(), partner_fee));
+
+                    admin_fee -= partner_fee; //deduct partner fee from admin fee
+                }
+            }
+        }
+
+        let config = self.config.load(storage)?;
+        shares.push((config.admin.to_string(), admin_fee));
+
+        let mut msgs: Vec<CosmosMsg<EvmMsg>> = Vec::new();
+        for (to_address, share) in shares {
+            // A zero amount send fails, skip empty shares
+            if share == 0 {
+                continue;
+            }
+            if collection.escrow == Some(EscrowStatus::Active) {
+                self.hold_payment(
+                    storage,
+                    collection_key,
+                    payer,
+                    &to_address,
+                    "usei",
+                    Uint128::new(share)
+                )?;
+            } else {
+                msgs.push(
+                    (BankMsg::Send {
+                        to_address,
+                        amount: coins(share, "usei"),
+                    }).into()
+                );
+            }
+        }
+
+        Ok(msgs)
+    }
+
+    /// Builds the messages minting the given 721 token ids to `recipient`.
+    fn create_721_mint_msgs(
+        &self,
+        collection: &Collection,
+        recipient: &str,
+        token_ids: &[u128]
+    ) -> Result<Vec<CosmosMsg<EvmMsg>>, ContractError> {
+        let collection_address = collection.collection_address.clone().unwrap();
+        let mut msgs: Vec<CosmosMsg<EvmMsg>> = Vec::new();
+
+        if collection.chain == "v1" {
+            // Init royalty extension, stock cw721-base takes no extension
+            let extension = match (&collection.cw721_flavor, &collection.stock_cw721) {
+                (Some(Cw721Flavor::Base), _) => None,
+                (Some(Cw721Flavor::Cw2981), Some(info)) =>
+                    Some(Cw2981Metadata {
+                        royalty_percentage: info.royalty_percentage,
+                        royalty_payment_address: info.royalty_payment_address.clone(),
+                        ..Cw2981Metadata::default()
+                    }),
+                _ =>
+                    Some(Cw2981Metadata {
+                        ..Cw2981Metadata::default()
+                    }),
+            };
+
+            for token_id in token_ids {
+                // Stock contracts store the token uri per token, the fork builds it from its base
+                let token_uri = collection.stock_cw721.as_ref().map(|info| {
+                    if info.fixed_uri {
+                        info.base_uri.clone()
+                    } else {
+                        format!(
+                            "{}{}{}",
+                            info.base_uri,
+                            token_id,
+                            info.uri_suffix.clone().unwrap_or_default()
+                        )
+                    }
+                });
+
+                // Prepare the mint message
+                let mint_msg = Cw2981ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: recipient.to_string(),
+                    token_uri,
+                    extension: extension.clone(),
+                };
+
+                //call cw721 contract
+                msgs.push(
+                    (WasmMsg::Execute {
+                        contract_addr: collection_address.clone(),
+                        msg: to_json_binary(&mint_msg)?,
+                        funds: vec![],
+                    }).into()
+                );
+            }
+        } else {
+            //call collection contract
+            let selector = &Keccak256::digest(b"mint(address,uint256)")[0..4];
+
+            let account_padded = pad_address_to_bytes32(recipient)?;
+
+            for token_id in token_ids {
+                let mut token_id_bytes = [0u8; 32];
+                token_id
+                    .to_be_bytes()
+                    .iter()
+                    .enumerate()
+                    .for_each(|(i, &b)| {
+                        token_id_bytes[32 - 16 + i] = b; //pad token id to 32 bytes
+                    });
+
+                let data = [selector, &account_padded[..], &token_id_bytes[..]].concat();
+                let data_base64 = BASE64.encode(&data);
+
+                msgs.push(
+                    (EvmMsg::CallEvm {
+                        to: collection_address.clone(),
+                        value: Uint128::zero(),
+                        data: data_base64,
+                    }).into()
+                );
+            }
+        }
+
+        Ok(msgs)
+    }
+
+    /// Builds the message minting `amount` units of the 1155 edition to `recipient`.
+    fn create_1155_mint_msg(
+        &self,
+        collection: &Collection,
+        recipient: &str,
+        edition_id: Uint128,
+        amount: Uint128
+    ) -> Result<CosmosMsg<EvmMsg>, ContractError> {
+        let collection_address = collection.collection_address.clone().unwrap();
+
+        if collection.chain == "v1" {
+            let mint_msg = Cw1155ExecuteMsg::Mint {
+                to: recipient.to_string(),
+                token_id: edition_id.to_string(),
+                value: amount,
+                msg: None,
+            };
+
+            return Ok(
+                (WasmMsg::Execute {
+                    contract_addr: collection_address,
+                    msg: to_json_binary(&mint_msg)?,
+                    funds: vec![],
+                }).into()
+            );
+        }
+
+        let selector = &Keccak256::digest(b"mint(address,uint256,uint256,bytes)")[0..4];
+        let account_padded = pad_address_to_bytes32(recipient)?;
+
+        let mut id_bytes = [0u8; 32];
+        id_bytes[16..].copy_from_slice(&edition_id.u128().to_be_bytes());
+        let mut amount_bytes = [0u8; 32];
+        amount_bytes[16..].copy_from_slice(&amount.u128().to_be_bytes());
+
+        // empty `bytes` argument: offset to the data (4 words in) and a zero length
+        let mut data_offset = [0u8; 32];
+        data_offset[31] = 0x80;
+        let data_length = [0u8; 32];
+
+        let data = [
+            selector,
+            &account_padded[..],
+            &id_bytes[..],
+            &amount_bytes[..],
+            &data_offset[..],
+            &data_length[..],
+        ].concat();
+
+        Ok(
+            (EvmMsg::CallEvm {
+                to: collection_address,
+                value: Uint128::zero(),
+                data: BASE64.encode(&data),
+            }).into()
+        )
+    }
+
+    /// Builds the message minting `amount` 404 tokens to `recipient`.
+    fn create_404_mint_msg(
+        &self,
+        collection: &Collection,
+        recipient: &str,
+        amount: Uint128
+    ) -> Result<CosmosMsg<EvmMsg>, ContractError> {
+        if collection.chain == "v2" {
+            let selector = &Keccak256::digest(b"mint(address,uint256)")[0..4];
+            let account_padded = pad_address_to_bytes32(recipient)?;
+
+            let mut amount_bytes = [0u8; 32];
+            amount_bytes[16..].copy_from_slice(&amount.u128().to_be_bytes());
+
+            let data = [selector, &account_padded[..], &amount_bytes[..]].concat();
+
+            return Ok(
+                (EvmMsg::CallEvm {
+                    to: collection.collection_address.clone().unwrap(),
+                    value: Uint128::zero(),
+                    data: BASE64.encode(&data),
+                }).into()
+            );
+        }
+
+        let mint_msg = Cw404ExecuteMsg::Mint {
+            recipient: recipient.to_string(),
+            amount,
+        };
+
+        Ok(
+            (WasmMsg::Execute {
+                contract_addr: collection.collection_address.clone().unwrap(),
+                msg: to_json_binary(&mint_msg)?,
+                funds: vec![],
+            }).into()
+        )
+    }
 }
@@ -1,8 +1,35 @@
-use cosmwasm_std::{ DepsMut, StdError, StdResult };
+use cosmwasm_std::{
+    coins,
+    to_json_binary,
+    Api,
+    BankMsg,
+    Binary,
+    CosmosMsg,
+    DepsMut,
+    StdError,
+    StdResult,
+    Uint128,
+    WasmMsg,
+};
 use data_encoding::HEXLOWER;
 use sha3::{ Digest, Keccak256 };
 use bech32::{decode, FromBase32};
-use crate::{msg::EvmQueryWrapper, state::{ MintGroup, PaymentType }};
+use crate::{
+    execute::ADMIN_MINT_GROUP,
+    msg::{ EvmQueryWrapper, MintVoucher },
+    querier::EvmQuerier,
+    state::{
+        Collection,
+        Cw404Info,
+        Cw721Flavor,
+        Milestone,
+        MintGroup,
+        PaymentType,
+        Raffle,
+        Vesting,
+    },
+    structs::{ Cw20ExecuteMsg, EvmMsg },
+};
 
 pub fn create_group_key(addr: &str, collection_addr: &str, group_name: &str) -> String {
     format!("{}_{}_{}", addr, collection_addr, group_name)
@@ -20,6 +47,76 @@ pub fn create_mint_log_key_404(collection_addr: &str, group_name: &str, order: &
     format!("{}_{}_{}", collection_addr, group_name, order)
 }
 
+pub fn create_random_pool_key(collection_addr: &str, position: u128) -> String {
+    format!("{}_{}", collection_addr, position)
+}
+
+/// Registry key of the approved codes for a collection type, 721 codes per cw721 flavor.
+pub fn create_code_registry_key(collection_type: &str, flavor: &Option<Cw721Flavor>) -> String {
+    match (collection_type, flavor) {
+        ("721", Some(Cw721Flavor::Base)) => "721-base".to_string(),
+        ("721", Some(Cw721Flavor::Cw2981)) => "721-cw2981".to_string(),
+        _ => collection_type.to_string(),
+    }
+}
+
+pub fn create_raffle_key(collection_addr: &str, group_name: &str) -> String {
+    format!("{}_{}", collection_addr, group_name)
+}
+
+/// Time after the entry end a raffle must be drawn by, unless the group sets a deadline
+pub const RAFFLE_DRAW_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+pub fn raffle_draw_deadline(raffle: &Raffle) -> u64 {
+    raffle.draw_deadline.unwrap_or(raffle.entry_end + RAFFLE_DRAW_WINDOW)
+}
+
+pub fn create_delegation_key(delegate: &str, collection_addr: Option<&str>) -> String {
+    match collection_addr {
+        Some(collection_addr) => format!("{}_{}", delegate, collection_addr),
+        None => delegate.to_string(),
+    }
+}
+
+pub fn create_voucher_key(collection_addr: &str, nonce: u64) -> String {
+    format!("{}_{}", collection_addr, nonce)
+}
+
+pub fn create_voucher_hash(
+    contract_addr: &str,
+    chain_id: &str,
+    collection_addr: &str,
+    group_name: &str,
+    minter: &str,
+    voucher: &MintVoucher
+) -> Vec<u8> {
+    let message = format!(
+        "{}|{}|{}|{}|{}|{}|{}|{}|{}",
+        contract_addr,
+        chain_id,
+        collection_addr,
+        group_name,
+        minter,
+        voucher.max_amount,
+        voucher.price.map(|p| p.to_string()).unwrap_or_default(),
+        voucher.expiry,
+        voucher.nonce
+    );
+
+    Keccak256::digest(message.as_bytes()).to_vec()
+}
+
+pub fn validate_voucher_signer(pubkey: &Option<Binary>) -> StdResult<()> {
+    if let Some(pubkey) = pubkey {
+        // compressed or uncompressed secp256k1 public key
+        if pubkey.len() != 33 && pubkey.len() != 65 {
+            return Err(StdError::generic_err("Invalid voucher signer public key"));
+        }
+    }
+
+    Ok(())
+}
+
 pub fn create_gated_mint_log_key(
     collection_addr: &str,
     contract_address: &str,
@@ -29,6 +126,22 @@ pub fn create_gated_mint_log_key(
     format!("{}_{}_{}_{}", collection_addr, contract_address, group_name, gated_token_id)
 }
 
+/// Resolves a bech32 or hex address to the bech32 address it is associated with.
+/// Hex addresses that are not associated yet are kept as lowercase hex.
+pub fn normalize_address(deps: &DepsMut<EvmQueryWrapper>, address: &str) -> StdResult<String> {
+    if address.starts_with("0x") {
+        pad_address_to_bytes32(address)?; // validate hex
+        let querier = EvmQuerier::new(&deps.querier);
+        let sei_address_query = querier.query_bech32_address(address.to_string())?;
+        if sei_address_query.associated {
+            return Ok(sei_address_query.sei_address);
+        }
+        return Ok(address.to_lowercase());
+    }
+
+    Ok(deps.api.addr_validate(address)?.to_string())
+}
+
 pub fn validate_merkle_proof(proof: Vec<Vec<u8>>, root: Vec<u8>, leaf: Vec<u8>) -> bool {
     let mut hash = leaf;
     for proof_hash in proof.into_iter() {
@@ -67,7 +180,7 @@ pub fn pad_address_to_bytes32(address: &str) -> StdResult<Vec<u8>> {
     Ok(padded)
 }
 
-pub fn validate_payments(deps:&DepsMut<EvmQueryWrapper>, groups: &Vec<MintGroup>) -> StdResult<()> {
+pub fn validate_payments(deps:&DepsMut<EvmQueryWrapper>, groups: &[MintGroup]) -> StdResult<()> {
     for group in groups.iter() {
         for payment in group.payments.iter() {
             match payment.payment_type {
@@ -83,10 +196,10 @@ pub fn validate_payments(deps:&DepsMut<EvmQueryWrapper>, groups: &Vec<MintGroup>
                         );
                     }
                     //args[0] must be the recipient address
-                    if payment.args.len() < 1 {
+                    if payment.args.is_empty() {
                         return Err(StdError::generic_err("Native payment recipient is required"));
                     }
-                    if let Err(_) = deps.api.addr_validate(&payment.args[0]) {
+                    if deps.api.addr_validate(&payment.args[0]).is_err() {
                         return Err(StdError::generic_err("Invalid recipient address"));
                     }
                 }
@@ -102,10 +215,10 @@ pub fn validate_payments(deps:&DepsMut<EvmQueryWrapper>, groups: &Vec<MintGroup>
                             )
                         );
                     }
-                    if let Err(_) =  deps.api.addr_validate(&payment.args[0]) {
+                    if deps.api.addr_validate(&payment.args[0]).is_err() {
                         return Err(StdError::generic_err("Invalid recipient address"));
                     }
-                    if let Err(_) =  deps.api.addr_validate(&payment.args[1]) {
+                    if deps.api.addr_validate(&payment.args[1]).is_err() {
                         return Err(StdError::generic_err("Invalid token contract address"));
                     }
                 }
@@ -114,14 +227,14 @@ pub fn validate_payments(deps:&DepsMut<EvmQueryWrapper>, groups: &Vec<MintGroup>
                         return Err(StdError::generic_err("Cw20 burn amount is required"));
                     }
                     //args[0] must be the recipient address and args[1] must be the token contract address
-                    if payment.args.len() < 1 {
+                    if payment.args.is_empty() {
                         return Err(
                             StdError::generic_err(
                                 "Cw20 burn token contract address is required"
                             )
                         );
                     }
-                    if let Err(_) =  deps.api.addr_validate(&payment.args[0]) {
+                    if deps.api.addr_validate(&payment.args[0]).is_err() {
                         return Err(StdError::generic_err("Invalid token contract address"));
                     }
                 }
@@ -135,7 +248,42 @@ pub fn validate_payments(deps:&DepsMut<EvmQueryWrapper>, groups: &Vec<MintGroup>
     Ok(())
 }
 
-pub fn validate_groups(collection_type: &String, groups: &Vec<MintGroup>) -> StdResult<()> {
+pub fn validate_groups(collection_type: &str, groups: &[MintGroup]) -> StdResult<()> {
+    // Admin mints are counted under their own group name
+    if groups.iter().any(|g| g.name == ADMIN_MINT_GROUP) {
+        return Err(StdError::generic_err("Mint group name is reserved"));
+    }
+
+    for group in groups.iter() {
+        if let Some(raffle) = &group.raffle {
+            if group.reserved_supply.is_zero() {
+                return Err(StdError::generic_err("Reserved supply is required for raffle groups"));
+            }
+            if raffle.entry_end < raffle.entry_start {
+                return Err(StdError::generic_err("Raffle entry end must be after entry start"));
+            }
+            if raffle.draw_deadline.is_some_and(|deadline| deadline <= raffle.entry_end) {
+                return Err(StdError::generic_err("Raffle draw deadline must be after entry end"));
+            }
+        }
+    }
+
+    for group in groups.iter() {
+        if let Some(presale) = &group.presale {
+            if presale.start_time >= group.start_time {
+                return Err(StdError::generic_err("Presale must start before the mint group"));
+            }
+            if group.raffle.is_some() || group.voucher_required == Some(true) {
+                return Err(
+                    StdError::generic_err("Presale cannot be combined with raffles or vouchers")
+                );
+            }
+            if group.payments.iter().any(|p| p.payment_type != PaymentType::Native) {
+                return Err(StdError::generic_err("Presale groups only accept native payments"));
+            }
+        }
+    }
+
     if collection_type == "404" {
         for group in groups.iter() {
             if group.batch_size.is_none() || group.batch_size.unwrap().is_zero() {
@@ -144,9 +292,169 @@ pub fn validate_groups(collection_type: &String, groups: &Vec<MintGroup>) -> Std
         }
     }
 
+    // Each 1155 group is an edition, with its reserved supply as the edition supply
+    if collection_type == "1155" {
+        for group in groups.iter() {
+            if group.edition_id.is_none() || group.reserved_supply.is_zero() {
+                return Err(
+                    StdError::generic_err("Edition id and reserved supply are required for 1155")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that 404 batches mint whole NFTs and that the supply fits the token cap.
+pub fn validate_404_supply(
+    supply: Uint128,
+    cw404_info: &Option<Cw404Info>,
+    groups: &[MintGroup]
+) -> StdResult<()> {
+    let info = cw404_info
+        .as_ref()
+        .ok_or(StdError::generic_err("cw404_info is required for 404 collection"))?;
+
+    if info.tokens_per_nft.is_zero() || supply > info.max_supply {
+        return Err(StdError::generic_err("Supply exceeds the 404 max supply"));
+    }
+
+    for group in groups.iter() {
+        if !(group.batch_size.unwrap_or_default() % info.tokens_per_nft).is_zero() {
+            return Err(StdError::generic_err("Batch size must be a multiple of tokens_per_nft"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Recovers the evm address that signed `message` with personal_sign.
+/// The signature is the 65 byte r || s || v form returned by wallets.
+pub fn recover_evm_address(api: &dyn Api, message: &[u8], signature: &[u8]) -> StdResult<String> {
+    if signature.len() != 65 {
+        return Err(StdError::generic_err("Invalid signature length"));
+    }
+
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()).as_bytes());
+    hasher.update(message);
+    let message_hash = hasher.finalize();
+
+    let recovery_param = match signature[64] {
+        27 | 28 => signature[64] - 27,
+        0 | 1 => signature[64],
+        _ => {
+            return Err(StdError::generic_err("Invalid signature recovery id"));
+        }
+    };
+
+    let pubkey = api.secp256k1_recover_pubkey(&message_hash, &signature[..64], recovery_param)?;
+
+    // evm address is the last 20 bytes of the keccak hash of the uncompressed public key
+    let pubkey_hash = Keccak256::digest(&pubkey[1..]);
+
+    Ok(format!("0x{}", bytes_to_hex(&pubkey_hash[12..])))
+}
+
+/// Checks if minting `amount` more tokens would go over the collection supply.
+/// For 404 collections the amount is in tokens, not batches, and for 1155 in edition units.
+/// Open editions have no cap until they are closed.
+pub fn exceeds_supply(collection: &Collection, amount: Uint128) -> bool {
+    if collection.open_edition.as_ref().is_some_and(|e| !e.closed) {
+        return false;
+    }
+
+    if collection.collection_type == "404" || collection.collection_type == "1155" {
+        return collection.next_token + amount > collection.supply;
+    }
+
+    collection.next_token + amount - collection.start_order.unwrap_or(Uint128::zero()) >
+        collection.supply
+}
+
+/// Closes an open edition whose window ended, fixing the supply to the minted count.
+/// Returns true if the edition was closed by this call.
+pub fn close_open_edition(collection: &mut Collection, now: u64) -> bool {
+    match collection.open_edition.as_mut() {
+        Some(edition) if !edition.closed && now >= edition.end_time => {
+            edition.closed = true;
+            collection.supply = collection.next_token - collection.start_order.unwrap_or_default();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Sends `amount` of `denom` from Lighthouse, where denom is "usei" or a cw20 contract address.
+pub fn create_payout_msg(
+    denom: &str,
+    recipient: &str,
+    amount: Uint128
+) -> StdResult<CosmosMsg<EvmMsg>> {
+    if denom == "usei" {
+        return Ok(
+            (BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins(amount.u128(), "usei"),
+            }).into()
+        );
+    }
+
+    Ok(
+        (WasmMsg::Execute {
+            contract_addr: denom.to_string(),
+            msg: to_json_binary(
+                &(Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount,
+                })
+            )?,
+            funds: vec![],
+        }).into()
+    )
+}
+
+pub fn validate_vesting(vesting: &Option<Vesting>) -> StdResult<()> {
+    if let Some(vesting) = vesting {
+        let milestones_percent = vesting.milestones.iter().fold(0, |acc, m| acc + m.percent);
+        if vesting.held_percent > 100 || milestones_percent > 100 {
+            return Err(StdError::generic_err("Vesting percent cannot be more than 100"));
+        }
+        if vesting.end_time < vesting.start_time {
+            return Err(StdError::generic_err("Vesting end time must be after start time"));
+        }
+    }
+
     Ok(())
 }
 
+/// Returns the vested part of `total` at `now`. Reached milestones unlock their share,
+/// and the rest vests linearly between the start and end time.
+pub fn vested_amount(
+    vesting: &Vesting,
+    reached: &[Milestone],
+    total: Uint128,
+    now: u64
+) -> Uint128 {
+    let milestones_percent = vesting.milestones.iter().fold(0u64, |acc, m| acc + m.percent);
+    let reached_percent = vesting.milestones
+        .iter()
+        .filter(|m| reached.contains(&m.milestone))
+        .fold(0u64, |acc, m| acc + m.percent);
+
+    let linear = total.multiply_ratio(100 - milestones_percent, 100u64);
+    let linear_vested = if now >= vesting.end_time {
+        linear
+    } else if now <= vesting.start_time {
+        Uint128::zero()
+    } else {
+        linear.multiply_ratio(now - vesting.start_time, vesting.end_time - vesting.start_time)
+    };
+
+    total.multiply_ratio(reached_percent, 100u64) + linear_vested
+}
+
 fn bytes_to_hex(bytes: &[u8]) -> String {
     bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<String>>().join("")
 }
@@ -1,9 +1,29 @@
-use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ Addr, CustomQuery, Uint128 };
+use cosmwasm_schema::{ cw_serde, QueryResponses };
+use cosmwasm_std::{ Addr, Binary, CustomQuery, Uint128 };
 use schemars::JsonSchema;
 use serde::{ Deserialize, Serialize };
 
-use crate::{ state::{Cw404Info, MintGroup}, structs::EvmQuery };
+use crate::{
+    state::{
+        AllowlistEntry,
+        Collection,
+        Config,
+        Cw404Info,
+        Delegation,
+        MintGroup,
+        MintInfo,
+        ApprovedCode,
+        Auction,
+        Cw721Flavor,
+        RaffleState,
+        RaffleTicket,
+        Reservation,
+        ReturnWindow,
+        TokenPayment,
+        Vesting,
+    },
+    structs::{ Cw721ReceiveMsg, EvmQuery, GetEvmAddressResponse, GetSeiAddressResponse },
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -17,13 +37,26 @@ pub struct UpdateConfig {
     pub registeration_open: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SetEmergencyStop {
+    pub stopped: bool,
+    pub reason: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RegisterCollection {
     pub cw721_code: Option<u64>,
     pub cw404_code: Option<u64>,
+    pub cw1155_code: Option<u64>,
     pub erc721_address: Option<String>,
+    pub cw721_address: Option<String>, // import a deployed cw721 instead of instantiating one
+    pub import_next_token: Option<Uint128>, // next token of an import, past its minted tokens
+    pub cw721_flavor: Option<Cw721Flavor>, // v1 721 only, defaults to the Lighthouse fork
+    pub predictable_address: Option<bool>, // v1 only, instantiate with a creator and symbol salt
+    pub erc1155_address: Option<String>,
+    pub erc404_address: Option<String>,
     pub chain: String, //v1,v2
-    pub collection_type: String, //721,404
+    pub collection_type: String, //721,404,1155
     pub name: String,
     pub symbol: String,
     pub supply: Uint128,
@@ -42,6 +75,14 @@ pub struct RegisterCollection {
     pub placeholder_token_uri: Option<String>,
     pub partner: Option<Addr>,
     pub cw404_info: Option<Cw404Info>,
+    pub voucher_signer: Option<Binary>,
+    pub random_seed_commitment: Option<Binary>, // keccak256 of a secret seed, for random token ids
+    pub provenance_hash: Option<String>,
+    pub reveal_time: Option<u64>,
+    pub escrow: Option<bool>, // hold proceeds and fees until the collection is finalized
+    pub vesting: Option<Vesting>,
+    pub return_window: Option<ReturnWindow>,
+    pub open_edition_end: Option<u64>, // if set, supply is unlimited until this time
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -58,6 +99,7 @@ pub struct UpdateCollection {
     pub placeholder_token_uri: Option<String>,
     pub max_edition: Option<u64>,
     pub frozen_whitelist: Option<Vec<Addr>>,
+    pub voucher_signer: Option<Binary>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -69,6 +111,33 @@ pub struct Mint {
     pub merkle_proof_address_type: Option<String>, // hex or bech32
     pub payment_args: Option<Vec<MintArg>>,
     pub gate_args: Option<Vec<MintArg>>,
+    pub voucher: Option<MintVoucher>,
+    pub evm_address_proof: Option<EvmAddressProof>,
+    pub vault: Option<String>, // mint on behalf of a vault that delegated to the sender
+    pub mint_to_vault: Option<bool>, // if true, tokens go to the vault instead of the sender
+    pub recipient: Option<String>, // bech32 or hex, mint as a gift to another wallet
+}
+
+/// Proves control of an evm address without associating it.
+/// The signature is a personal_sign of "{contract}|{chain_id}|{minter}", where contract is
+/// this Lighthouse contract's address and minter is the bech32 address of the minting wallet.
+/// Mint limits are counted against the evm address, as they are for associated wallets.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EvmAddressProof {
+    pub address: String,
+    pub signature: Binary,
+}
+
+/// Signed by the collection's voucher_signer over keccak256(
+/// "{contract}|{chain_id}|{collection}|{group}|{minter}|{max_amount}|{price}|{expiry}|{nonce}"),
+/// where contract is this Lighthouse contract's address, with an empty string for a missing price.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintVoucher {
+    pub max_amount: Uint128,
+    pub price: Option<Uint128>, // usei per token, replaces the group's native payments
+    pub expiry: u64,
+    pub nonce: u64,
+    pub signature: Binary,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -87,6 +156,148 @@ pub struct RevealCollectionMetadata {
     pub collection: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RevealRandomSeed {
+    pub collection: String,
+    pub seed: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseCollection {
+    pub collection: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResumeCollection {
+    pub collection: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FinalizeCollection {
+    pub collection: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CancelCollection {
+    pub collection: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimRefund {
+    pub collection: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimProceeds {
+    pub collection: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReturnToken {
+    pub collection: String,
+    pub token_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReleaseReturnFunds {
+    pub collection: String,
+    pub token_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EnterRaffle {
+    pub collection: String,
+    pub group: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DrawRaffle {
+    pub collection: String,
+    pub group: String,
+    pub seed: Binary,
+    pub limit: Option<u32>, // winners to draw in this call, anyone can continue a started draw
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimRaffleRefund {
+    pub collection: String,
+    pub group: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreateAuction {
+    pub collection: String,
+    pub token_id: Uint128,
+    pub payee: Option<String>, // defaults to the collection admin
+    pub start_time: u64,
+    pub end_time: u64,
+    pub reserve_price: Uint128,
+    pub min_increment: Option<Uint128>,
+    pub extension_window: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlaceBid {
+    pub collection: String,
+    pub token_id: Uint128,
+    pub recipient: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SettleAuction {
+    pub collection: String,
+    pub token_id: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CancelAuction {
+    pub collection: String,
+    pub token_id: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Reserve {
+    pub collection: String,
+    pub group: String,
+    pub amount: Uint128,
+    pub merkle_proof: Option<Vec<Vec<u8>>>,
+    pub merkle_proof_address_type: Option<String>,
+    pub evm_address_proof: Option<EvmAddressProof>,
+    pub vault: Option<String>, // reserve on behalf of a vault that delegated to the sender
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Claim {
+    pub collection: String,
+    pub group: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CloseOpenEdition {
+    pub collection: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApproveCode {
+    pub collection_type: String,
+    pub cw721_flavor: Option<Cw721Flavor>,
+    pub code_id: u64,
+    pub label: String,
+    pub default: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RevokeCode {
+    pub collection_type: String,
+    pub cw721_flavor: Option<Cw721Flavor>,
+    pub code_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemovePendingRegistration {
+    pub reply_id: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct UpdateAdmin {
     pub collection: String,
@@ -111,7 +322,53 @@ pub struct UpdateNftContractAdmin {
     pub new_admin: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowlistEntryMsg {
+    pub address: String, // bech32 or hex
+    pub allocation: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AddToAllowlist {
+    pub collection: String,
+    pub group: String,
+    pub entries: Vec<AllowlistEntryMsg>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemoveFromAllowlist {
+    pub collection: String,
+    pub group: String,
+    pub addresses: Vec<String>, // bech32 or hex
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DelegateWallet {
+    pub delegate: String,
+    pub collection: Option<String>, // None for all collections
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RevokeDelegation {
+    pub delegate: String,
+    pub collection: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminMintEntry {
+    pub recipient: String, // bech32 or hex
+    pub amount: Uint128, // token count for 721, token amount for 404 and 1155
+    pub edition_id: Option<Uint128>, // required for 1155
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminMint {
+    pub collection: String,
+    pub mints: Vec<AdminMintEntry>,
+}
+
 #[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     UpdateConfig(UpdateConfig),
     RegisterCollection(RegisterCollection),
@@ -123,32 +380,162 @@ pub enum ExecuteMsg {
     AddPartner(AddPartner),
     UpdateNftContractCwOwnableOwner(UpdateNftContractCwOwnableOwner),
     UpdateNftContractAdmin(UpdateNftContractAdmin),
+    AddToAllowlist(AddToAllowlist),
+    RemoveFromAllowlist(RemoveFromAllowlist),
+    DelegateWallet(DelegateWallet),
+    RevokeDelegation(RevokeDelegation),
+    AdminMint(AdminMint),
+    RevealRandomSeed(RevealRandomSeed),
+    PauseCollection(PauseCollection),
+    ResumeCollection(ResumeCollection),
+    SetEmergencyStop(SetEmergencyStop),
+    FinalizeCollection(FinalizeCollection),
+    CancelCollection(CancelCollection),
+    ClaimRefund(ClaimRefund),
+    ClaimProceeds(ClaimProceeds),
+    ReceiveNft(Cw721ReceiveMsg),
+    ReturnToken(ReturnToken),
+    ReleaseReturnFunds(ReleaseReturnFunds),
+    EnterRaffle(EnterRaffle),
+    DrawRaffle(DrawRaffle),
+    ClaimRaffleRefund(ClaimRaffleRefund),
+    CreateAuction(CreateAuction),
+    PlaceBid(PlaceBid),
+    SettleAuction(SettleAuction),
+    CancelAuction(CancelAuction),
+    Reserve(Reserve),
+    Claim(Claim),
+    CloseOpenEdition(CloseOpenEdition),
+    ApproveCode(ApproveCode),
+    RevokeCode(RevokeCode),
+    RemovePendingRegistration(RemovePendingRegistration),
 }
 
 #[cw_serde]
+#[derive(QueryResponses)]
 pub enum QueryMsg {
+    #[returns(Config)]
     GetConfig {},
+    #[returns(Collection)]
     GetCollection {
         collection: String,
     },
+    #[returns(ProvenanceResponse)]
+    GetProvenance {
+        collection: String,
+    },
+    #[returns(Vec<EscrowAmount>)]
+    GetEscrowPayments {
+        collection: String,
+        address: String,
+    },
+    #[returns(Vec<EscrowAmount>)]
+    GetEscrowBalances {
+        collection: String,
+        payee: String,
+    },
+    #[returns(Vec<VestingResponse>)]
+    GetVesting {
+        collection: String,
+        payee: String,
+    },
+    #[returns(MintInfo)]
     MintsOf {
         address: String,
         collection: String,
     },
+    #[returns(String)]
     GetMinterOf {
         collection: String,
         token_id: String,
     },
+    #[returns(RaffleState)]
+    GetRaffle {
+        collection: String,
+        group_name: String,
+    },
+    #[returns(Vec<RaffleTicket>)]
+    GetRaffleTickets {
+        collection: String,
+        group_name: String,
+        winners_only: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(Reservation)]
+    GetReservation {
+        collection: String,
+        group_name: String,
+        address: String,
+    },
+    #[returns(Uint128)]
+    GetReservedTotal {
+        collection: String,
+        group_name: String,
+    },
+    #[returns(Vec<PendingRegistration>)]
+    GetPendingRegistrations {
+        creator: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Vec<ApprovedCode>)]
+    GetApprovedCodes {
+        collection_type: String,
+        cw721_flavor: Option<Cw721Flavor>,
+    },
+    #[returns(Auction)]
+    GetAuction {
+        collection: String,
+        token_id: String,
+    },
+    #[returns(Vec<Auction>)]
+    GetAuctions {
+        collection: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(TokenPayment)]
+    GetTokenPayment {
+        collection: String,
+        token_id: String,
+    },
+    #[returns(String)]
+    GetRecipientOf {
+        collection: String,
+        token_id: String,
+    },
+    #[returns(Uint128)]
     GetGlobalMintInfo {
         collection: String,
         group_name: String,
     },
+    #[returns(GetEvmAddressResponse)]
     GetEvmAddressOfBech32Address {
         address: String,
     },
+    #[returns(GetSeiAddressResponse)]
     GetBech32AddressOfEvmAddress {
         address: String,
     },
+    #[returns(Vec<Delegation>)]
+    GetDelegations {
+        vault: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(bool)]
+    IsVoucherUsed {
+        collection: String,
+        nonce: u64,
+    },
+    #[returns(Vec<AllowlistEntry>)]
+    GetAllowlist {
+        collection: String,
+        group_name: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /*GetGatedMintsOf {
         collection: String,
         group_name: String,
@@ -157,6 +544,34 @@ pub enum QueryMsg {
     },*/
 }
 
+#[cw_serde]
+pub struct PendingRegistration {
+    pub reply_id: u64,
+    pub collection: Collection,
+}
+
+#[cw_serde]
+pub struct ProvenanceResponse {
+    pub provenance_hash: Option<String>,
+    pub reveal_time: Option<u64>,
+    pub revealed: bool,
+}
+
+#[cw_serde]
+pub struct EscrowAmount {
+    pub denom: String, // usei or cw20 contract address
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct VestingResponse {
+    pub denom: String,
+    pub total: Uint128,
+    pub vested: Uint128,
+    pub claimed: Uint128,
+    pub locked: Uint128,
+}
+
 //evm stuff
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -27,7 +27,7 @@ impl<'a> EvmQuerier<'a> {
             &address
         )?;
 
-        let data = [&selector[..], &role[..], &account_padded[..]].concat();
+        let data = [selector, &role[..], &account_padded[..]].concat();
         let data_base64 = BASE64.encode(&data);
 
         let request: QueryRequest<EvmQueryWrapper> = (EvmQueryWrapper {
@@ -39,7 +39,7 @@ impl<'a> EvmQuerier<'a> {
             },
         }).into();
 
-        Ok(self.query(&request)? != Binary::from(vec![0u8; 32]))
+        Ok(self.query(&request)?.as_slice() != [0u8; 32])
     }
 
     /*pub fn query_address(
@@ -84,7 +84,7 @@ impl<'a> EvmQuerier<'a> {
         }.into();
     
         let res = self.query(&request)?;
-        Ok(from_json(&res)?)
+        from_json(&res)
     }
 
     pub fn query_bech32_address(
@@ -100,7 +100,7 @@ impl<'a> EvmQuerier<'a> {
         }.into();
     
         let res = self.query(&request)?;
-        Ok(from_json(&res)?)
+        from_json(&res)
     }
 
     pub fn query(&self, request: &QueryRequest<EvmQueryWrapper>) -> StdResult<Binary> {
@@ -1,30 +1,102 @@
-use cosmwasm_std::{ to_json_binary, Binary, Deps, StdResult };
+use cosmwasm_std::{ to_json_binary, Binary, Deps, Env, Order, StdResult };
+use cw_storage_plus::Bound;
 
 use crate::{
     helpers::{
+        close_open_edition,
+        create_code_registry_key,
         create_global_mint_info_key,
         create_group_key,
         create_mint_log_key,
+        create_raffle_key,
+        create_voucher_key,
+        vested_amount,
+    },
+    msg::{
+        EscrowAmount,
+        EvmQueryWrapper,
+        PendingRegistration,
+        ProvenanceResponse,
+        QueryMsg,
+        VestingResponse,
     },
-    msg::{ EvmQueryWrapper, QueryMsg },
     querier::EvmQuerier,
-    state::{ Lighthouse, MintInfo },
+    state::{
+        AllowlistEntry,
+        ApprovedCode,
+        Auction,
+        Cw721Flavor,
+        Delegation,
+        Lighthouse,
+        MintInfo,
+        RaffleState,
+        RaffleTicket,
+    },
 };
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
 impl<'a> Lighthouse<'a> {
-    pub fn query(&self, deps: Deps<EvmQueryWrapper>, msg: QueryMsg) -> StdResult<Binary> {
+    pub fn query(&self, deps: Deps<EvmQueryWrapper>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
             QueryMsg::GetConfig {} => self.query_get_config(deps),
-            QueryMsg::GetCollection { collection } => self.get_collection(deps, collection),
+            QueryMsg::GetCollection { collection } => self.get_collection(deps, env, collection),
+            QueryMsg::GetProvenance { collection } => self.get_provenance(deps, collection),
+            QueryMsg::GetEscrowPayments { collection, address } =>
+                self.get_escrow_payments(deps, collection, address),
+            QueryMsg::GetEscrowBalances { collection, payee } =>
+                self.get_escrow_balances(deps, collection, payee),
+            QueryMsg::GetVesting { collection, payee } =>
+                self.get_vesting(deps, env, collection, payee),
             QueryMsg::MintsOf { address, collection } => self.mints_of(deps, address, collection),
             QueryMsg::GetMinterOf { collection, token_id } =>
                 self.get_minter_of(deps, collection, token_id),
+            QueryMsg::GetRaffle { collection, group_name } =>
+                self.get_raffle(deps, collection, group_name),
+            QueryMsg::GetRaffleTickets {
+                collection,
+                group_name,
+                winners_only,
+                start_after,
+                limit,
+            } =>
+                self.get_raffle_tickets(
+                    deps,
+                    collection,
+                    group_name,
+                    winners_only,
+                    start_after,
+                    limit
+                ),
+            QueryMsg::GetReservation { collection, group_name, address } =>
+                self.get_reservation(deps, collection, group_name, address),
+            QueryMsg::GetReservedTotal { collection, group_name } =>
+                self.get_reserved_total(deps, collection, group_name),
+            QueryMsg::GetPendingRegistrations { creator, start_after, limit } =>
+                self.get_pending_registrations(deps, creator, start_after, limit),
+            QueryMsg::GetApprovedCodes { collection_type, cw721_flavor } =>
+                self.get_approved_codes(deps, collection_type, cw721_flavor),
+            QueryMsg::GetAuction { collection, token_id } =>
+                self.get_auction(deps, collection, token_id),
+            QueryMsg::GetAuctions { collection, start_after, limit } =>
+                self.get_auctions(deps, collection, start_after, limit),
+            QueryMsg::GetTokenPayment { collection, token_id } =>
+                self.get_token_payment(deps, collection, token_id),
+            QueryMsg::GetRecipientOf { collection, token_id } =>
+                self.get_recipient_of(deps, collection, token_id),
             QueryMsg::GetGlobalMintInfo { collection, group_name } =>
                 self.get_global_mint_info(deps, collection, group_name),
             QueryMsg::GetEvmAddressOfBech32Address { address } =>
                 self.get_evm_address_of_bech32_address(deps, address),
             QueryMsg::GetBech32AddressOfEvmAddress { address } =>
                 self.get_bech32_address_of_evm_address(deps, address),
+            QueryMsg::GetDelegations { vault, start_after, limit } =>
+                self.get_delegations(deps, vault, start_after, limit),
+            QueryMsg::IsVoucherUsed { collection, nonce } =>
+                self.is_voucher_used(deps, collection, nonce),
+            QueryMsg::GetAllowlist { collection, group_name, start_after, limit } =>
+                self.get_allowlist(deps, collection, group_name, start_after, limit),
             /* QueryMsg::GetGatedMintsOf { collection, group_name, contract_address, token_ids } =>
                 self.get_gated_mints_of(deps, collection, group_name, contract_address, token_ids),*/
         }
@@ -38,12 +110,95 @@ impl<'a> Lighthouse<'a> {
     pub fn get_collection(
         &self,
         deps: Deps<EvmQueryWrapper>,
+        env: Env,
         collection: String
     ) -> StdResult<Binary> {
-        let collection = self.collections.load(deps.storage, collection)?;
+        let mut collection = self.collections.load(deps.storage, collection)?;
+        // Report the final supply of an ended open edition, even before it is closed on chain
+        close_open_edition(&mut collection, env.block.time.seconds());
         to_json_binary(&collection)
     }
 
+    pub fn get_provenance(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String
+    ) -> StdResult<Binary> {
+        let collection = self.collections.load(deps.storage, collection)?;
+        to_json_binary(
+            &(ProvenanceResponse {
+                provenance_hash: collection.provenance_hash,
+                reveal_time: collection.reveal_time,
+                revealed: !collection.hidden_metadata,
+            })
+        )
+    }
+
+    pub fn get_escrow_payments(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        address: String
+    ) -> StdResult<Binary> {
+        let payments = self.escrow_payments
+            .prefix((collection, address))
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| EscrowAmount { denom, amount }))
+            .collect::<StdResult<Vec<EscrowAmount>>>()?;
+
+        to_json_binary(&payments)
+    }
+
+    pub fn get_escrow_balances(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        payee: String
+    ) -> StdResult<Binary> {
+        let balances = self.escrow_balances
+            .prefix((collection, payee))
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| EscrowAmount { denom, amount }))
+            .collect::<StdResult<Vec<EscrowAmount>>>()?;
+
+        to_json_binary(&balances)
+    }
+
+    pub fn get_vesting(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        env: Env,
+        collection: String,
+        payee: String
+    ) -> StdResult<Binary> {
+        let collection_info = self.collections.load(deps.storage, collection.clone())?;
+        let reached = collection_info.reached_milestones.unwrap_or_default();
+        let now = env.block.time.seconds();
+
+        let balances = self.vesting_balances
+            .prefix((collection, payee))
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                item.map(|(denom, balance)| {
+                    let vested = match &collection_info.vesting {
+                        Some(vesting) =>
+                            vested_amount(vesting, &reached, balance.total, now),
+                        None => balance.total,
+                    };
+                    VestingResponse {
+                        denom,
+                        total: balance.total,
+                        vested,
+                        claimed: balance.claimed,
+                        locked: balance.total - vested,
+                    }
+                })
+            })
+            .collect::<StdResult<Vec<VestingResponse>>>()?;
+
+        to_json_binary(&balances)
+    }
+
     pub fn mints_of(
         &self,
         deps: Deps<EvmQueryWrapper>,
@@ -51,16 +206,23 @@ impl<'a> Lighthouse<'a> {
         collection: String
     ) -> StdResult<Binary> {
         let collection_info = self.collections.load(deps.storage, collection.clone())?;
-        let mut mints = vec![];
+        let mut mints = MintInfo {
+            mints: vec![],
+            editions: None,
+        };
         for group in collection_info.mint_groups {
             let key = create_group_key(&address, &collection, &group.name);
             let mint_info = self.mint_info.load(deps.storage, key).unwrap_or(MintInfo {
                 mints: vec![],
+                editions: None,
             });
-            mints.extend(mint_info.mints);
+            mints.mints.extend(mint_info.mints);
+            for edition in mint_info.editions.unwrap_or_default() {
+                mints.add_edition(edition.edition_id, edition.quantity);
+            }
         }
 
-        to_json_binary(&(MintInfo { mints }))
+        to_json_binary(&mints)
     }
 
     pub fn get_minter_of(
@@ -74,6 +236,168 @@ impl<'a> Lighthouse<'a> {
         to_json_binary(&minter)
     }
 
+    pub fn get_raffle(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        group_name: String
+    ) -> StdResult<Binary> {
+        let key = create_raffle_key(&collection, &group_name);
+        let state = self.raffles.may_load(deps.storage, key)?.unwrap_or(RaffleState {
+            entries: 0,
+            winners: 0,
+            seed: None,
+            drawn: None,
+            entropy: None,
+        });
+        to_json_binary(&state)
+    }
+
+    pub fn get_raffle_tickets(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        group_name: String,
+        winners_only: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+        let winners_only = winners_only.unwrap_or(false);
+
+        let tickets = self.raffle_tickets
+            .prefix(create_raffle_key(&collection, &group_name))
+            .range(deps.storage, start, None, Order::Ascending)
+            .filter(|item| item.as_ref().map(|(_, t)| !winners_only || t.won).unwrap_or(true))
+            .take(limit)
+            .map(|item| item.map(|(_, ticket)| ticket))
+            .collect::<StdResult<Vec<RaffleTicket>>>()?;
+
+        to_json_binary(&tickets)
+    }
+
+    pub fn get_reservation(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        group_name: String,
+        address: String
+    ) -> StdResult<Binary> {
+        let key = create_group_key(&address, &collection, &group_name);
+        let reservation = self.reservations.load(deps.storage, key)?;
+        to_json_binary(&reservation)
+    }
+
+    pub fn get_reserved_total(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        group_name: String
+    ) -> StdResult<Binary> {
+        let key = create_global_mint_info_key(&collection, &group_name);
+        let total = self.reserved_totals.may_load(deps.storage, key)?.unwrap_or_default();
+        to_json_binary(&total)
+    }
+
+    pub fn get_pending_registrations(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        creator: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let pending = self.instantiates
+            .range(deps.storage, start, None, Order::Ascending)
+            .filter(|item| {
+                match (item, &creator) {
+                    (Ok((_, collection)), Some(creator)) => collection.admin == *creator,
+                    _ => true,
+                }
+            })
+            .take(limit)
+            .map(|item|
+                item.map(|(reply_id, collection)| PendingRegistration { reply_id, collection })
+            )
+            .collect::<StdResult<Vec<PendingRegistration>>>()?;
+
+        to_json_binary(&pending)
+    }
+
+    pub fn get_approved_codes(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection_type: String,
+        cw721_flavor: Option<Cw721Flavor>
+    ) -> StdResult<Binary> {
+        let codes = self.approved_codes
+            .prefix(create_code_registry_key(&collection_type, &cw721_flavor))
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, code)| code))
+            .collect::<StdResult<Vec<ApprovedCode>>>()?;
+
+        to_json_binary(&codes)
+    }
+
+    pub fn get_auction(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        token_id: String
+    ) -> StdResult<Binary> {
+        let auction = self.auctions.load(deps.storage, (collection, token_id))?;
+        to_json_binary(&auction)
+    }
+
+    pub fn get_auctions(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        start_after: Option<String>,
+        limit: Option<u32>
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let auctions = self.auctions
+            .prefix(collection)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, auction)| auction))
+            .collect::<StdResult<Vec<Auction>>>()?;
+
+        to_json_binary(&auctions)
+    }
+
+    pub fn get_token_payment(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        token_id: String
+    ) -> StdResult<Binary> {
+        let key = create_mint_log_key(&collection, &token_id);
+        let payment = self.token_payments.load(deps.storage, key)?;
+        to_json_binary(&payment)
+    }
+
+    pub fn get_recipient_of(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        token_id: String
+    ) -> StdResult<Binary> {
+        let key = create_mint_log_key(&collection, &token_id);
+        // tokens minted before recipients were logged went to the minter
+        let recipient = match self.recipient_logs.may_load(deps.storage, key.clone())? {
+            Some(recipient) => recipient,
+            None => self.mint_logs.load(deps.storage, key)?,
+        };
+        to_json_binary(&recipient)
+    }
+
     pub fn get_global_mint_info(
         &self,
         deps: Deps<EvmQueryWrapper>,
@@ -107,6 +431,57 @@ impl<'a> Lighthouse<'a> {
         to_json_binary(&bech32_address)
     }
 
+    pub fn get_delegations(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        vault: String,
+        start_after: Option<String>,
+        limit: Option<u32>
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let delegations = self.delegations
+            .prefix(vault)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, delegation)| delegation))
+            .collect::<StdResult<Vec<Delegation>>>()?;
+
+        to_json_binary(&delegations)
+    }
+
+    pub fn is_voucher_used(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        nonce: u64
+    ) -> StdResult<Binary> {
+        let key = create_voucher_key(&collection, nonce);
+        to_json_binary(&self.used_vouchers.has(deps.storage, key))
+    }
+
+    pub fn get_allowlist(
+        &self,
+        deps: Deps<EvmQueryWrapper>,
+        collection: String,
+        group_name: String,
+        start_after: Option<String>,
+        limit: Option<u32>
+    ) -> StdResult<Binary> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let entries = self.allowlists
+            .prefix((collection, group_name))
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, entry)| entry))
+            .collect::<StdResult<Vec<AllowlistEntry>>>()?;
+
+        to_json_binary(&entries)
+    }
+
     /*pub fn get_gated_mints_of(
         &self,
         deps: Deps,
@@ -1,15 +1,55 @@
 use cosmwasm_std::{DepsMut, Reply, Response};
-use cw_utils::parse_reply_instantiate_data;
+use cw_utils::{parse_reply_instantiate_data, ParseReplyError};
 
 use crate::{state::Lighthouse, ContractError};
 
 impl<'a> Lighthouse<'a> {
     pub fn reply(&self, deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
-        let instantiate_info = self.instantiates.load(deps.storage, msg.id)?;
+        let instantiate_info = self.instantiates
+            .may_load(deps.storage, msg.id)?
+            .ok_or(ContractError::UnknownReplyId {})?;
 
-        let reply = parse_reply_instantiate_data(msg.clone()).unwrap();
+        let reply = match parse_reply_instantiate_data(msg.clone()) {
+            Ok(reply) => reply,
+            Err(ParseReplyError::SubMsgFailure(error)) => {
+                // The instantiation failed, drop the pending registration (and the collection
+                // registered up front for Instantiate2) so nothing is left behind
+                self.instantiates.remove(deps.storage, msg.id);
+                if let Some(address) = instantiate_info.collection_address.clone() {
+                    self.collections.remove(deps.storage, address);
+                }
+
+                return Ok(
+                    Response::new()
+                        .add_attribute("register_collection", "failed")
+                        .add_attribute("reply_id", msg.id.to_string())
+                        .add_attribute("error", error)
+                );
+            }
+            Err(error) => {
+                return Err(error.into());
+            }
+        };
 
         let mut collection = instantiate_info.clone();
+
+        // Collections instantiated with Instantiate2 are already registered under the
+        // predicted address, the reply only confirms it
+        if let Some(address) = instantiate_info.collection_address {
+            if address != reply.contract_address {
+                return Err(ContractError::UnexpectedCollectionAddress {});
+            }
+            self.instantiates.remove(deps.storage, msg.id);
+
+            return Ok(
+                Response::new()
+                    .add_attribute("register_collection", "success")
+                    .add_attribute("collection", address)
+                    .add_attribute("type", collection.collection_type)
+                    .add_attribute("chain", collection.chain)
+            );
+        }
+
         collection.collection_address = Some(reply.contract_address);
 
         if self.collections.has(deps.storage, collection.collection_address.clone().unwrap()) {
@@ -28,4 +68,4 @@ impl<'a> Lighthouse<'a> {
                 .add_attribute("chain", collection.chain)
         )
     }
-}
\ No newline at end of file
+}
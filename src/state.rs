@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Uint128};
 use cw_storage_plus::{Item, Map};
 
 pub struct Lighthouse<'a>{
@@ -9,8 +9,28 @@ pub struct Lighthouse<'a>{
     pub mint_info: Map<'a, String, MintInfo>,
     //pub gated_mint_info: Map<'a, String, String>,
     pub mint_logs: Map<'a, String, String>,
+    pub recipient_logs: Map<'a, String, String>,
     pub partners: Map<'a, String, Partner>,
     pub instantiates: Map<'a, u64, Collection>,
+    pub allowlists: Map<'a, (String, String, String), AllowlistEntry>,
+    pub used_vouchers: Map<'a, String, bool>,
+    pub delegations: Map<'a, (String, String), Delegation>,
+    pub random_pool: Map<'a, String, u128>,
+    pub escrow_balances: Map<'a, (String, String, String), Uint128>, // (collection, payee, denom)
+    pub escrow_payments: Map<'a, (String, String, String), Uint128>, // (collection, payer, denom)
+    // (collection, payee, denom)
+    pub vesting_balances: Map<'a, (String, String, String), VestingBalance>,
+    pub token_payments: Map<'a, String, TokenPayment>,
+    pub raffles: Map<'a, String, RaffleState>,
+    pub raffle_entries: Map<'a, (String, u64), String>, // (raffle, index) -> entrant
+    pub raffle_tickets: Map<'a, (String, String), RaffleTicket>, // (raffle, entrant)
+    pub raffle_draws: Map<'a, (String, u64), u64>, // (raffle, position) -> swapped entry index
+    pub auctions: Map<'a, (String, String), Auction>, // (collection, token_id)
+    pub auctioned_ids: Map<'a, (String, u128), bool>, // ids kept out of the mint range
+    pub reservations: Map<'a, String, Reservation>, // keyed like mint_info
+    pub reserved_totals: Map<'a, String, Uint128>, // group key -> unclaimed reserved amount
+    pub wallet_reserved: Map<'a, String, Uint128>, // limit owner key -> unclaimed reserved amount
+    pub approved_codes: Map<'a, (String, u64), ApprovedCode>, // (code registry key, code_id)
 }
 
 impl Default for Lighthouse<'static> {
@@ -28,8 +48,27 @@ impl<'a> Lighthouse<'a> {
             mint_info: Map::new("mint_info"),
             //gated_mint_info: Map::new("gated_mint_info"),
             mint_logs: Map::new("mint_logs"),
+            recipient_logs: Map::new("recipient_logs"),
             partners: Map::new("partners"),
             instantiates: Map::new("instantiates"),
+            allowlists: Map::new("allowlists"),
+            used_vouchers: Map::new("used_vouchers"),
+            delegations: Map::new("delegations"),
+            random_pool: Map::new("random_pool"),
+            escrow_balances: Map::new("escrow_balances"),
+            escrow_payments: Map::new("escrow_payments"),
+            vesting_balances: Map::new("vesting_balances"),
+            token_payments: Map::new("token_payments"),
+            raffles: Map::new("raffles"),
+            raffle_entries: Map::new("raffle_entries"),
+            raffle_tickets: Map::new("raffle_tickets"),
+            raffle_draws: Map::new("raffle_draws"),
+            auctions: Map::new("auctions"),
+            auctioned_ids: Map::new("auctioned_ids"),
+            reservations: Map::new("reservations"),
+            reserved_totals: Map::new("reserved_totals"),
+            wallet_reserved: Map::new("wallet_reserved"),
+            approved_codes: Map::new("approved_codes"),
         }
     }
 }
@@ -40,7 +79,8 @@ pub struct Config {
     pub admin: Addr,
     pub fee: Uint128,
     pub registeration_open: bool,
-    pub next_reply_id: u64
+    pub next_reply_id: u64,
+    pub emergency_stop: Option<bool>, // if true, only the stop, revokes, refunds and claims run
 }
 
 //COLLECTION
@@ -48,7 +88,7 @@ pub struct Config {
 pub struct Collection {
     pub admin: Addr,
     pub chain: String, //v1,v2
-    pub collection_type: String, //721,404
+    pub collection_type: String, //721,404,1155
     pub collection_address: Option<String>,
     pub name: String,
     pub symbol: String,
@@ -62,6 +102,56 @@ pub struct Collection {
     pub placeholder_token_uri: Option<String>,
     pub partner: Option<Addr>,
     pub cw404_info: Option<Cw404Info>,
+    pub voucher_signer: Option<Binary>, // secp256k1 public key of the off-chain voucher authorizer
+    pub random_seed_commitment: Option<Binary>, // keccak256 of the creator seed, for random ids
+    pub random_seed: Option<Binary>, // creator seed, once revealed
+    pub provenance_hash: Option<String>, // hash of the final metadata, committed at registration
+    pub reveal_time: Option<u64>, // anyone can reveal the metadata after this time
+    pub paused_at: Option<u64>, // set while minting is paused
+    pub paused_by: Option<Addr>, // who paused, only the platform admin can lift its own pause
+    pub escrow: Option<EscrowStatus>, // if set, proceeds and fees are held until finalized
+    pub vesting: Option<Vesting>, // if set, a share of the proceeds vests to the payees
+    pub reached_milestones: Option<Vec<Milestone>>,
+    pub return_window: Option<ReturnWindow>, // if set, holders can return tokens for a refund
+    pub open_edition: Option<OpenEdition>, // if set, supply is unlimited until the edition ends
+    pub cw721_flavor: Option<Cw721Flavor>, // v1 721 contract flavor, the Lighthouse fork if unset
+    pub stock_cw721: Option<StockCw721Info>, // token uri and royalty data for stock flavors
+}
+
+#[cw_serde]
+pub enum Cw721Flavor {
+    Lighthouse,
+    Base, // stock cw721-base
+    Cw2981, // stock cw2981-royalties
+}
+
+#[cw_serde]
+pub struct StockCw721Info {
+    pub base_uri: String,
+    pub uri_suffix: Option<String>,
+    pub fixed_uri: bool,
+    pub royalty_percentage: Option<u64>,
+    pub royalty_payment_address: Option<String>,
+}
+
+impl Collection {
+    /// Returns true if the collection is a stock cw721-base or cw2981-royalties contract.
+    pub fn is_stock_cw721(&self) -> bool {
+        matches!(self.cw721_flavor, Some(Cw721Flavor::Base) | Some(Cw721Flavor::Cw2981))
+    }
+}
+
+#[cw_serde]
+pub struct OpenEdition {
+    pub end_time: u64,
+    pub closed: bool, // once closed, supply is the minted count
+}
+
+#[cw_serde]
+pub enum EscrowStatus {
+    Active,
+    Finalized,
+    Cancelled,
 }
 
 #[cw_serde]
@@ -90,8 +180,30 @@ pub struct MintGroup {
     pub end_time: u64,
     pub payments: Vec<Payment>,
     pub batch_size: Option<Uint128>, //for 404
+    pub edition_id: Option<Uint128>, //for 1155, the token id minted by the group
     pub gates: Vec<Gate>, // TODO: implement
     pub gates_optional: Option<bool>, // if true, any gate must be passed. if false, all gates must be passed
+    pub allowlist: Option<bool>, // if true and no merkle_root is set, minters must be on the on-chain allowlist
+    pub voucher_required: Option<bool>, // if true, mints must carry a voucher signed by the voucher_signer
+    pub raffle: Option<Raffle>, // if set, reserved_supply winners are drawn from the entrants
+    pub presale: Option<Presale>, // if set, tokens can be reserved and paid for before start_time
+}
+
+#[cw_serde]
+pub struct Presale {
+    pub start_time: u64, // reservations are open from here until the group's start_time
+    pub claim_on_reveal: Option<bool>, // if true, reservations are claimable once revealed
+}
+
+#[cw_serde]
+pub struct Raffle {
+    pub entry_start: u64,
+    pub entry_end: u64,
+    pub deposit: Option<Uint128>, // usei, counted as payment for winners and refunded to the rest
+    pub seed_commitment: Binary, // keccak256 of the creator seed revealed at the draw
+    // if the draw is not complete by then every entrant can claim a refund,
+    // defaults to a week after entry_end
+    pub draw_deadline: Option<u64>,
 }
 
 #[cw_serde]
@@ -145,10 +257,171 @@ impl Default for Gate {
     }
 }
 
+//VESTING
+#[cw_serde]
+pub struct Vesting {
+    pub held_percent: u64, // share of each payment that vests
+    pub start_time: u64,
+    pub end_time: u64,
+    pub milestones: Vec<VestingMilestone>, // the rest vests linearly from start_time to end_time
+}
+
+#[cw_serde]
+pub struct VestingMilestone {
+    pub milestone: Milestone,
+    pub percent: u64, // share of the held proceeds unlocked when reached
+}
+
+#[cw_serde]
+pub enum Milestone {
+    SoldOut,
+    Reveal,
+}
+
+#[cw_serde]
+pub struct VestingBalance {
+    pub total: Uint128,
+    pub claimed: Uint128,
+}
+
+//RETURN WINDOW
+#[cw_serde]
+pub struct ReturnWindow {
+    pub duration: u64, // seconds after the mint
+    pub refund_percent: u64, // share of the price refunded, held until the window closes
+}
+
+#[cw_serde]
+pub struct TokenPayment {
+    pub returnable_until: u64,
+    pub held: Vec<HeldPayment>,
+}
+
+#[cw_serde]
+pub struct HeldPayment {
+    pub payee: String,
+    pub denom: String,
+    pub amount: Uint128,
+}
+
+//RAFFLE
+#[cw_serde]
+pub struct RaffleState {
+    pub entries: u64,
+    pub winners: u64,
+    pub seed: Option<Binary>, // set once the draw started
+    pub drawn: Option<u64>, // winners drawn so far, the draw can span several batches
+    pub entropy: Option<Binary>,
+}
+
+impl RaffleState {
+    pub fn is_drawn(&self) -> bool {
+        self.seed.is_some() && self.drawn.unwrap_or(self.winners) >= self.winners
+    }
+}
+
+#[cw_serde]
+pub struct RaffleTicket {
+    pub address: String,
+    pub index: u64,
+    pub won: bool,
+    pub minted: bool,
+    pub refunded: bool,
+}
+
+//AUCTION
+#[cw_serde]
+pub struct Auction {
+    pub token_id: Uint128,
+    pub payee: String,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub reserve_price: Uint128, // usei
+    pub min_increment: Uint128,
+    pub extension_window: u64, // bids within this many seconds of the end push the end back
+    pub highest_bid: Option<Bid>,
+    pub status: AuctionStatus,
+}
+
+#[cw_serde]
+pub struct Bid {
+    pub bidder: Addr,
+    pub recipient: String, // resolved for the collection's chain when the bid is placed
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub enum AuctionStatus {
+    Active,
+    Settled,
+    Cancelled,
+}
+
+//CODE REGISTRY
+#[cw_serde]
+pub struct ApprovedCode {
+    pub kind: String, // 721, 721-base, 721-cw2981, 404 or 1155
+    pub code_id: u64,
+    pub label: String,
+    pub default: bool,
+}
+
+//PRESALE
+#[cw_serde]
+pub struct Reservation {
+    pub amount: Uint128,
+    pub fee: Uint128, // platform fee per token at reservation time
+    pub payouts: Vec<HeldPayment>, // usei per token and payee at reservation time
+    pub limit_owner: Option<String>, // the key mint limits count under, the buyer if unset
+}
+
 //MINTINFO
 #[cw_serde]
 pub struct MintInfo {
-    pub mints: Vec<Uint128>
+    pub mints: Vec<Uint128>,
+    pub editions: Option<Vec<EditionMints>>, // 1155 mints, one entry per edition
+}
+
+#[cw_serde]
+pub struct EditionMints {
+    pub edition_id: Uint128,
+    pub quantity: Uint128,
+}
+
+impl MintInfo {
+    /// Number of tokens (or edition units) minted, as counted for the wallet limits.
+    pub fn count(&self) -> u128 {
+        let editions = self.editions
+            .iter()
+            .flatten()
+            .fold(0u128, |acc, e| acc + e.quantity.u128());
+        (self.mints.len() as u128) + editions
+    }
+
+    pub fn add_edition(&mut self, edition_id: Uint128, quantity: Uint128) {
+        let editions = self.editions.get_or_insert_with(Vec::new);
+        match editions.iter_mut().find(|e| e.edition_id == edition_id) {
+            Some(entry) => {
+                entry.quantity += quantity;
+            }
+            None => editions.push(EditionMints { edition_id, quantity }),
+        }
+    }
+}
+
+//ALLOWLIST
+#[cw_serde]
+pub struct AllowlistEntry {
+    pub address: String, // bech32, or lowercase hex if not associated
+    pub allocation: Option<Uint128>, // per address mint limit, on top of max_mints_per_wallet
+}
+
+//DELEGATION
+#[cw_serde]
+pub struct Delegation {
+    pub vault: Addr,
+    pub delegate: Addr,
+    pub collection: Option<String>, // None for all collections
 }
 
 //PARTNER
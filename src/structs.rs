@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, CosmosMsg, CustomMsg, Uint128};
+use cosmwasm_std::{Addr, Binary, CosmosMsg, CustomMsg, Uint128};
 use cw_utils::Expiration;
 use schemars::JsonSchema;
 use serde::{Serialize,Deserialize};
@@ -65,6 +65,21 @@ pub struct Cw404InstantiateMsg {
 }
 
 
+#[cw_serde]
+pub struct Cw1155InstantiateMsg {
+    pub minter: String,
+}
+
+#[cw_serde]
+pub enum Cw1155ExecuteMsg {
+    Mint {
+        to: String,
+        token_id: String,
+        value: Uint128,
+        msg: Option<Binary>,
+    },
+}
+
 #[cw_serde]
 pub struct MinterResponse {
     pub minter: String,
@@ -109,6 +124,10 @@ impl CustomMsg for Cw404ExecuteMsg {}
 
 #[cw_serde]
 pub enum Cw20ExecuteMsg {
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+    },
     TransferFrom {
         owner: String,
         recipient: String,
@@ -133,6 +152,26 @@ pub enum Cw721QueryMsg {
         token_id: String,
         include_expired: Option<bool>,
     },
+    NumTokens {},
+    Minter {},
+    Ownership {},
+}
+
+#[cw_serde]
+pub struct Cw721NumTokensResponse {
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct Cw721MinterResponse {
+    pub minter: Option<String>, // a plain string before cw721 0.18, which parses the same
+}
+
+#[cw_serde]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
 }
 
 #[cw_serde]
@@ -199,6 +238,15 @@ pub struct MintInfo {
     pub mints: Vec<u32>
 }
 
+/// Who a mint or reservation is for: the wallet checked for eligibility (the sender or
+/// the vault it mints for), the evm address it controls and the key its limits count under
+#[cw_serde]
+pub struct Minter {
+    pub address: Addr,
+    pub evm_address: Option<String>,
+    pub limit_owner: String,
+}
+
 #[cw_serde]
 pub struct GatedInfo {
     pub contract_addr: String,
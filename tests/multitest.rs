@@ -0,0 +1,1059 @@
+use anyhow::{ bail, Result as AnyResult };
+use cosmwasm_std::{
+    coins,
+    testing::{ MockApi, MockStorage },
+    to_json_binary,
+    Addr,
+    Api,
+    Binary,
+    BlockInfo,
+    CustomQuery,
+    Deps,
+    DepsMut,
+    Empty,
+    Env,
+    MessageInfo,
+    Querier,
+    Reply,
+    Response,
+    StdError,
+    StdResult,
+    Storage,
+    Timestamp,
+    Uint128,
+};
+use cw2981_royalties::{
+    Cw2981Contract,
+    ExecuteMsg as Cw2981ExecuteMsg,
+    QueryMsg as Cw2981QueryMsg,
+};
+use cw_multi_test::{
+    App,
+    AppBuilder,
+    AppResponse,
+    BankKeeper,
+    ContractWrapper,
+    CosmosRouter,
+    Executor,
+    Module,
+    WasmKeeper,
+};
+use lighthouse::{
+    contract,
+    msg::{
+        AddToAllowlist,
+        AllowlistEntryMsg,
+        ApproveCode,
+        CancelCollection,
+        Claim,
+        ClaimProceeds,
+        ClaimRaffleRefund,
+        ClaimRefund,
+        CreateAuction,
+        DelegateWallet,
+        DrawRaffle,
+        EnterRaffle,
+        EvmQueryWrapper,
+        ExecuteMsg,
+        FinalizeCollection,
+        InstantiateMsg,
+        Mint,
+        PlaceBid,
+        QueryMsg,
+        RegisterCollection,
+        ReleaseReturnFunds,
+        Reserve,
+        ReturnToken,
+        RevokeDelegation,
+        SetEmergencyStop,
+        SettleAuction,
+        UpdateCollection,
+    },
+    state::{
+        Cw721Flavor,
+        Milestone,
+        MintGroup,
+        Payment,
+        PaymentType,
+        Presale,
+        Raffle,
+        RaffleState,
+        RaffleTicket,
+        ReturnWindow,
+        Vesting,
+        VestingMilestone,
+    },
+    structs::{ EvmMsg, EvmQuery, GetEvmAddressResponse, GetSeiAddressResponse },
+    ContractError,
+};
+use schemars::JsonSchema;
+use serde::{ de::DeserializeOwned, Deserialize };
+use sha3::{ Digest, Keccak256 };
+
+const ADMIN: &str = "admin";
+const CREATOR: &str = "creator";
+const START: u64 = 1_700_000_000;
+const BALANCE: u128 = 1_000_000;
+
+type LighthouseApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    EvmModule,
+    WasmKeeper<EvmMsg, EvmQueryWrapper>
+>;
+
+/// Sei EVM module stand-in: no address is associated and evm calls are accepted as no-ops.
+struct EvmModule;
+
+impl Module for EvmModule {
+    type ExecT = EvmMsg;
+    type QueryT = EvmQueryWrapper;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        _msg: EvmMsg
+    ) -> AnyResult<AppResponse>
+        where
+            ExecC: std::fmt::Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+            QueryC: CustomQuery + DeserializeOwned + 'static
+    {
+        Ok(AppResponse::default())
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty
+    ) -> AnyResult<AppResponse>
+        where
+            ExecC: std::fmt::Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+            QueryC: CustomQuery + DeserializeOwned + 'static
+    {
+        bail!("sudo is not supported")
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: EvmQueryWrapper
+    ) -> AnyResult<Binary> {
+        match request.query_data {
+            EvmQuery::GetEvmAddress { .. } =>
+                Ok(
+                    to_json_binary(
+                        &(GetEvmAddressResponse {
+                            evm_address: String::new(),
+                            associated: false,
+                        })
+                    )?
+                ),
+            EvmQuery::GetSeiAddress { .. } =>
+                Ok(
+                    to_json_binary(
+                        &(GetSeiAddressResponse {
+                            sei_address: String::new(),
+                            associated: false,
+                        })
+                    )?
+                ),
+            EvmQuery::StaticCall { .. } => bail!("static calls are not supported"),
+        }
+    }
+}
+
+// Lighthouse instantiates and replies without the evm querier, adapt them to the app types
+
+fn lighthouse_instantiate(
+    deps: DepsMut<EvmQueryWrapper>,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg
+) -> Result<Response<EvmMsg>, ContractError> {
+    let res = contract::instantiate(deps.into_empty(), env, info, msg)?;
+    Ok(Response::new().add_attributes(res.attributes))
+}
+
+fn lighthouse_reply(
+    deps: DepsMut<EvmQueryWrapper>,
+    env: Env,
+    msg: Reply
+) -> Result<Response<EvmMsg>, ContractError> {
+    let res = contract::reply(deps.into_empty(), env, msg)?;
+    Ok(Response::new().add_attributes(res.attributes))
+}
+
+fn nft_instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: cw2981_royalties::InstantiateMsg
+) -> StdResult<Response> {
+    Cw2981Contract::default().instantiate(deps, env, info, msg)
+}
+
+fn nft_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw2981ExecuteMsg
+) -> StdResult<Response> {
+    Cw2981Contract::default()
+        .execute(deps, env, info, msg)
+        .map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+fn nft_query(deps: Deps, env: Env, msg: Cw2981QueryMsg) -> StdResult<Binary> {
+    Cw2981Contract::default().query(deps, env, msg)
+}
+
+#[derive(Deserialize)]
+struct OwnerOf {
+    owner: String,
+}
+
+struct Suite {
+    app: LighthouseApp,
+    lighthouse: Addr,
+}
+
+impl Suite {
+    fn new(fee: u128) -> Self {
+        let mut app = AppBuilder::new_custom()
+            .with_custom(EvmModule)
+            .build(|router, _api, storage| {
+                for user in ["buyer", "buyer1", "buyer2", "buyer3"] {
+                    router.bank
+                        .init_balance(storage, &Addr::unchecked(user), coins(BALANCE, "usei"))
+                        .unwrap();
+                }
+            });
+        app.update_block(|block| {
+            block.time = Timestamp::from_seconds(START);
+        });
+
+        let lighthouse_code = app.store_code(
+            Box::new(
+                ContractWrapper::new(contract::execute, lighthouse_instantiate, contract::query)
+                    .with_reply(lighthouse_reply)
+            )
+        );
+        let nft_code = app.store_code(
+            Box::new(ContractWrapper::new_with_empty(nft_execute, nft_instantiate, nft_query))
+        );
+
+        let lighthouse = app
+            .instantiate_contract(
+                lighthouse_code,
+                Addr::unchecked(ADMIN),
+                &(InstantiateMsg {
+                    fee: Uint128::new(fee),
+                    registeration_open: true,
+                }),
+                &[],
+                "lighthouse",
+                None
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            lighthouse.clone(),
+            &ExecuteMsg::ApproveCode(ApproveCode {
+                collection_type: "721".to_string(),
+                cw721_flavor: Some(Cw721Flavor::Cw2981),
+                code_id: nft_code,
+                label: "cw2981-royalties".to_string(),
+                default: true,
+            }),
+            &[]
+        ).unwrap();
+
+        Suite { app, lighthouse }
+    }
+
+    fn now(&self) -> u64 {
+        self.app.block_info().time.seconds()
+    }
+
+    fn set_time(&mut self, seconds: u64) {
+        self.app.update_block(|block| {
+            block.time = Timestamp::from_seconds(seconds);
+        });
+    }
+
+    fn execute(&mut self, sender: &str, msg: ExecuteMsg, funds: u128) -> AnyResult<AppResponse> {
+        let funds = if funds == 0 { vec![] } else { coins(funds, "usei") };
+        self.app.execute_contract(Addr::unchecked(sender), self.lighthouse.clone(), &msg, &funds)
+    }
+
+    fn register(&mut self, msg: RegisterCollection) -> String {
+        let res = self.execute(CREATOR, ExecuteMsg::RegisterCollection(msg), 0).unwrap();
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == "collection")
+            .map(|attr| attr.value.clone())
+            .unwrap()
+    }
+
+    fn mint(&mut self, sender: &str, collection: &str, group: &str, amount: u128, funds: u128) {
+        self.execute(sender, ExecuteMsg::Mint(mint_msg(collection, group, amount)), funds).unwrap();
+    }
+
+    fn balance(&self, address: &str) -> u128 {
+        self.app.wrap().query_balance(address, "usei").unwrap().amount.u128()
+    }
+
+    fn owner_of(&self, collection: &str, token_id: &str) -> StdResult<String> {
+        let owner: OwnerOf = self.app.wrap().query_wasm_smart(
+            collection,
+            &(Cw2981QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            })
+        )?;
+        Ok(owner.owner)
+    }
+
+    fn query<T: DeserializeOwned>(&self, msg: &QueryMsg) -> T {
+        self.app.wrap().query_wasm_smart(self.lighthouse.clone(), msg).unwrap()
+    }
+}
+
+fn error(result: AnyResult<AppResponse>) -> ContractError {
+    result.unwrap_err().downcast().unwrap()
+}
+
+fn group(name: &str, price: u128) -> MintGroup {
+    MintGroup {
+        name: name.to_string(),
+        merkle_root: None,
+        max_mints_per_wallet: Uint128::zero(),
+        reserved_supply: Uint128::zero(),
+        start_time: 0,
+        end_time: 0,
+        payments: vec![Payment {
+            payment_type: PaymentType::Native,
+            amount: Some(Uint128::new(price)),
+            args: vec![CREATOR.to_string()],
+        }],
+        batch_size: None,
+        edition_id: None,
+        gates: vec![],
+        gates_optional: None,
+        allowlist: None,
+        voucher_required: None,
+        raffle: None,
+        presale: None,
+    }
+}
+
+fn register_msg(supply: u128, mint_groups: Vec<MintGroup>) -> RegisterCollection {
+    RegisterCollection {
+        cw721_code: None,
+        cw404_code: None,
+        cw1155_code: None,
+        erc721_address: None,
+        cw721_address: None,
+        import_next_token: None,
+        cw721_flavor: Some(Cw721Flavor::Cw2981),
+        predictable_address: None,
+        erc1155_address: None,
+        erc404_address: None,
+        chain: "v1".to_string(),
+        collection_type: "721".to_string(),
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        supply: Uint128::new(supply),
+        token_uri: "ipfs://test/".to_string(),
+        royalty_percent: None,
+        royalty_wallet: None,
+        mint_groups,
+        is_immutable: false,
+        fixed_uri: false,
+        start_order: None,
+        uri_suffix: None,
+        frozen: false,
+        unfreeze_time: None,
+        frozen_whitelist: None,
+        hidden_metadata: false,
+        placeholder_token_uri: None,
+        partner: None,
+        cw404_info: None,
+        voucher_signer: None,
+        random_seed_commitment: None,
+        provenance_hash: None,
+        reveal_time: None,
+        escrow: None,
+        vesting: None,
+        return_window: None,
+        open_edition_end: None,
+    }
+}
+
+fn update_msg(collection: &str, supply: u128, mint_groups: Vec<MintGroup>) -> UpdateCollection {
+    UpdateCollection {
+        collection: collection.to_string(),
+        supply: Uint128::new(supply),
+        token_uri: "ipfs://test/".to_string(),
+        royalty_percent: None,
+        royalty_wallet: None,
+        mint_groups,
+        fixed_uri: false,
+        uri_suffix: None,
+        start_order: None,
+        placeholder_token_uri: None,
+        max_edition: None,
+        frozen_whitelist: None,
+        voucher_signer: None,
+    }
+}
+
+fn mint_msg(collection: &str, group: &str, amount: u128) -> Mint {
+    Mint {
+        collection: collection.to_string(),
+        group: group.to_string(),
+        amount: Uint128::new(amount),
+        merkle_proof: None,
+        merkle_proof_address_type: None,
+        payment_args: None,
+        gate_args: None,
+        voucher: None,
+        evm_address_proof: None,
+        vault: None,
+        mint_to_vault: None,
+        recipient: None,
+    }
+}
+
+#[test]
+fn escrow_cancel_refunds_price_and_fee() {
+    let mut suite = Suite::new(10);
+    let mut msg = register_msg(10, vec![group("public", 100)]);
+    msg.escrow = Some(true);
+    let collection = suite.register(msg);
+
+    suite.mint("buyer", &collection, "public", 2, 220);
+
+    // Proceeds and fee are both held by Lighthouse
+    assert_eq!(suite.balance(suite.lighthouse.as_str()), 220);
+    assert_eq!(suite.balance(CREATOR), 0);
+    assert_eq!(suite.balance(ADMIN), 0);
+
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::CancelCollection(CancelCollection { collection: collection.clone() }),
+            0
+        )
+        .unwrap();
+    suite
+        .execute(
+            "buyer",
+            ExecuteMsg::ClaimRefund(ClaimRefund { collection: collection.clone() }),
+            0
+        )
+        .unwrap();
+
+    assert_eq!(suite.balance("buyer"), BALANCE);
+    assert_eq!(suite.balance(suite.lighthouse.as_str()), 0);
+
+    let err = error(
+        suite.execute("buyer", ExecuteMsg::ClaimRefund(ClaimRefund { collection }), 0)
+    );
+    assert!(matches!(err, ContractError::NothingToClaim {}));
+}
+
+#[test]
+fn emergency_stop_still_pays_out_refunds() {
+    let mut suite = Suite::new(10);
+    let mut msg = register_msg(10, vec![group("public", 100)]);
+    msg.escrow = Some(true);
+    let collection = suite.register(msg);
+
+    suite.mint("buyer", &collection, "public", 1, 110);
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::CancelCollection(CancelCollection { collection: collection.clone() }),
+            0
+        )
+        .unwrap();
+    suite
+        .execute(
+            ADMIN,
+            ExecuteMsg::SetEmergencyStop(SetEmergencyStop {
+                stopped: true,
+                reason: "incident".to_string(),
+            }),
+            0
+        )
+        .unwrap();
+
+    let err = error(
+        suite.execute(
+            "buyer1",
+            ExecuteMsg::Mint(mint_msg(&collection, "public", 1)),
+            110
+        )
+    );
+    assert!(matches!(err, ContractError::EmergencyStop {}));
+
+    suite
+        .execute("buyer", ExecuteMsg::ClaimRefund(ClaimRefund { collection }), 0)
+        .unwrap();
+    assert_eq!(suite.balance("buyer"), BALANCE);
+}
+
+#[test]
+fn escrow_finalize_releases_proceeds_and_fee() {
+    let mut suite = Suite::new(10);
+    let mut msg = register_msg(10, vec![group("public", 100)]);
+    msg.escrow = Some(true);
+    let collection = suite.register(msg);
+
+    suite.mint("buyer", &collection, "public", 1, 110);
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::FinalizeCollection(FinalizeCollection { collection: collection.clone() }),
+            0
+        )
+        .unwrap();
+
+    assert_eq!(suite.balance(CREATOR), 100);
+    assert_eq!(suite.balance(ADMIN), 10);
+    assert_eq!(suite.balance(suite.lighthouse.as_str()), 0);
+
+    let err = error(
+        suite.execute("buyer", ExecuteMsg::ClaimRefund(ClaimRefund { collection }), 0)
+    );
+    assert!(matches!(err, ContractError::InvalidEscrowStatus {}));
+}
+
+#[test]
+fn vesting_claims_follow_time_and_milestones() {
+    let mut suite = Suite::new(0);
+    let now = suite.now();
+    let mut msg = register_msg(2, vec![group("public", 1000)]);
+    msg.vesting = Some(Vesting {
+        held_percent: 50,
+        start_time: now,
+        end_time: now + 100,
+        milestones: vec![VestingMilestone {
+            milestone: Milestone::SoldOut,
+            percent: 20,
+        }],
+    });
+    let collection = suite.register(msg);
+
+    // Selling out reaches the milestone, half of the price is paid out directly
+    suite.mint("buyer", &collection, "public", 2, 2000);
+    assert_eq!(suite.balance(CREATOR), 1000);
+
+    // 20% on the milestone, plus half of the linear 80%
+    suite.set_time(now + 50);
+    let claim = ExecuteMsg::ClaimProceeds(ClaimProceeds { collection: collection.clone() });
+    suite.execute(CREATOR, claim.clone(), 0).unwrap();
+    assert_eq!(suite.balance(CREATOR), 1000 + 200 + 400);
+
+    suite.set_time(now + 100);
+    suite.execute(CREATOR, claim.clone(), 0).unwrap();
+    assert_eq!(suite.balance(CREATOR), 2000);
+
+    let err = error(suite.execute(CREATOR, claim, 0));
+    assert!(matches!(err, ContractError::NothingToClaim {}));
+}
+
+#[test]
+fn return_refunds_holder_and_release_skips_returned_tokens() {
+    let mut suite = Suite::new(0);
+    let now = suite.now();
+    let mut msg = register_msg(10, vec![group("public", 100)]);
+    msg.return_window = Some(ReturnWindow {
+        duration: 100,
+        refund_percent: 50,
+    });
+    let collection = suite.register(msg);
+
+    suite.mint("buyer", &collection, "public", 2, 200);
+    assert_eq!(suite.balance(CREATOR), 100);
+    assert_eq!(suite.owner_of(&collection, "0").unwrap(), "buyer");
+
+    // Return token 0 for the refundable half of its price
+    suite.app
+        .execute_contract(
+            Addr::unchecked("buyer"),
+            Addr::unchecked(&collection),
+            &(Cw2981ExecuteMsg::Approve {
+                spender: suite.lighthouse.to_string(),
+                token_id: "0".to_string(),
+                expires: None,
+            }),
+            &[]
+        )
+        .unwrap();
+    suite
+        .execute(
+            "buyer",
+            ExecuteMsg::ReturnToken(ReturnToken {
+                collection: collection.clone(),
+                token_id: "0".to_string(),
+            }),
+            0
+        )
+        .unwrap();
+    assert_eq!(suite.balance("buyer"), BALANCE - 200 + 50);
+    assert!(suite.owner_of(&collection, "0").is_err());
+
+    let release = ExecuteMsg::ReleaseReturnFunds(ReleaseReturnFunds {
+        collection: collection.clone(),
+        token_ids: vec!["0".to_string(), "1".to_string()],
+    });
+    assert!(suite.execute(CREATOR, release.clone(), 0).is_err());
+
+    // Once the window closed the kept token's share is released, the returned one is skipped
+    suite.set_time(now + 101);
+    suite.execute(CREATOR, release.clone(), 0).unwrap();
+    assert_eq!(suite.balance(CREATOR), 150);
+
+    let err = error(suite.execute(CREATOR, release, 0));
+    assert!(matches!(err, ContractError::NothingToClaim {}));
+}
+
+fn raffle_group(now: u64, seed: &[u8], draw_deadline: Option<u64>) -> MintGroup {
+    let mut group = group("raffle", 100);
+    group.reserved_supply = Uint128::new(2);
+    group.start_time = now + 20;
+    group.end_time = now + 1000;
+    group.raffle = Some(Raffle {
+        entry_start: now,
+        entry_end: now + 10,
+        deposit: Some(Uint128::new(100)),
+        seed_commitment: Binary::from(Keccak256::digest(seed).to_vec()),
+        draw_deadline,
+    });
+    group
+}
+
+fn enter_raffle(suite: &mut Suite, collection: &str, entrants: &[&str]) {
+    for entrant in entrants {
+        let msg = ExecuteMsg::EnterRaffle(EnterRaffle {
+            collection: collection.to_string(),
+            group: "raffle".to_string(),
+        });
+        suite.execute(entrant, msg, 100).unwrap();
+    }
+}
+
+#[test]
+fn raffle_draws_in_batches_and_refunds_deposits() {
+    let mut suite = Suite::new(0);
+    let now = suite.now();
+    let seed = b"raffle seed";
+    let collection = suite.register(register_msg(10, vec![raffle_group(now, seed, None)]));
+
+    enter_raffle(&mut suite, &collection, &["buyer1", "buyer2", "buyer3"]);
+    suite.set_time(now + 11);
+
+    let draw = |limit| {
+        ExecuteMsg::DrawRaffle(DrawRaffle {
+            collection: collection.clone(),
+            group: "raffle".to_string(),
+            seed: Binary::from(seed.to_vec()),
+            limit: Some(limit),
+        })
+    };
+
+    // Only the admin can start the draw, anyone can continue it
+    let err = error(suite.execute("buyer1", draw(1), 0));
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    suite.execute(CREATOR, draw(1), 0).unwrap();
+    let state: RaffleState = suite.query(
+        &(QueryMsg::GetRaffle {
+            collection: collection.clone(),
+            group_name: "raffle".to_string(),
+        })
+    );
+    assert_eq!(state.drawn, Some(1));
+    assert!(!state.is_drawn());
+
+    suite.execute("buyer1", draw(1), 0).unwrap();
+    let err = error(suite.execute("buyer1", draw(1), 0));
+    assert!(matches!(err, ContractError::RaffleAlreadyDrawn {}));
+
+    let tickets: Vec<RaffleTicket> = suite.query(
+        &(QueryMsg::GetRaffleTickets {
+            collection: collection.clone(),
+            group_name: "raffle".to_string(),
+            winners_only: None,
+            start_after: None,
+            limit: None,
+        })
+    );
+    let winners: Vec<&RaffleTicket> = tickets
+        .iter()
+        .filter(|t| t.won)
+        .collect();
+    let loser = tickets
+        .iter()
+        .find(|t| !t.won)
+        .unwrap();
+    assert_eq!(winners.len(), 2);
+
+    let refund = ExecuteMsg::ClaimRaffleRefund(ClaimRaffleRefund {
+        collection: collection.clone(),
+        group: "raffle".to_string(),
+    });
+    suite.execute(&loser.address, refund.clone(), 0).unwrap();
+    assert_eq!(suite.balance(&loser.address), BALANCE);
+
+    // Winners are refunded only if they did not mint before the group ended
+    let err = error(suite.execute(&winners[0].address, refund.clone(), 0));
+    assert!(matches!(err, ContractError::NothingToClaim {}));
+
+    suite.set_time(now + 20);
+    suite.mint(&winners[0].address, &collection, "raffle", 1, 0);
+    assert_eq!(suite.balance(CREATOR), 100);
+
+    suite.set_time(now + 1001);
+    suite.execute(&winners[1].address, refund.clone(), 0).unwrap();
+    assert_eq!(suite.balance(&winners[1].address), BALANCE);
+    let err = error(suite.execute(&winners[0].address, refund, 0));
+    assert!(matches!(err, ContractError::NothingToClaim {}));
+}
+
+#[test]
+fn raffle_refunds_winners_once_the_collection_sold_out() {
+    let mut suite = Suite::new(0);
+    let now = suite.now();
+    let seed = b"raffle seed";
+    let mut group = raffle_group(now, seed, None);
+    group.end_time = 0;
+    let collection = suite.register(register_msg(1, vec![group]));
+
+    enter_raffle(&mut suite, &collection, &["buyer1", "buyer2"]);
+    suite.set_time(now + 20);
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::DrawRaffle(DrawRaffle {
+                collection: collection.clone(),
+                group: "raffle".to_string(),
+                seed: Binary::from(seed.to_vec()),
+                limit: None,
+            }),
+            0
+        )
+        .unwrap();
+
+    let refund = ExecuteMsg::ClaimRaffleRefund(ClaimRaffleRefund {
+        collection: collection.clone(),
+        group: "raffle".to_string(),
+    });
+    let err = error(suite.execute("buyer2", refund.clone(), 0));
+    assert!(matches!(err, ContractError::NothingToClaim {}));
+
+    // Both entrants won, but the supply only covers one of them
+    suite.mint("buyer1", &collection, "raffle", 1, 0);
+    suite.execute("buyer2", refund, 0).unwrap();
+    assert_eq!(suite.balance("buyer2"), BALANCE);
+}
+
+#[test]
+fn raffle_refunds_everyone_after_the_draw_deadline() {
+    let mut suite = Suite::new(0);
+    let now = suite.now();
+    let seed = b"raffle seed";
+    let group = raffle_group(now, seed, Some(now + 15));
+    let collection = suite.register(register_msg(10, vec![group]));
+
+    enter_raffle(&mut suite, &collection, &["buyer1", "buyer2"]);
+
+    let refund = ExecuteMsg::ClaimRaffleRefund(ClaimRaffleRefund {
+        collection: collection.clone(),
+        group: "raffle".to_string(),
+    });
+    suite.set_time(now + 15);
+    let err = error(suite.execute("buyer1", refund.clone(), 0));
+    assert!(matches!(err, ContractError::RaffleNotDrawn {}));
+
+    suite.set_time(now + 16);
+    let draw = ExecuteMsg::DrawRaffle(DrawRaffle {
+        collection: collection.clone(),
+        group: "raffle".to_string(),
+        seed: Binary::from(seed.to_vec()),
+        limit: None,
+    });
+    let err = error(suite.execute(CREATOR, draw, 0));
+    assert!(matches!(err, ContractError::RaffleDrawExpired {}));
+
+    for entrant in ["buyer1", "buyer2"] {
+        suite.execute(entrant, refund.clone(), 0).unwrap();
+        assert_eq!(suite.balance(entrant), BALANCE);
+    }
+}
+
+#[test]
+fn auction_refunds_outbid_bidder_and_settles_without_fee() {
+    let mut suite = Suite::new(0);
+    let now = suite.now();
+    let collection = suite.register(register_msg(5, vec![group("public", 100)]));
+
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::CreateAuction(CreateAuction {
+                collection: collection.clone(),
+                token_id: Uint128::new(100),
+                payee: None,
+                start_time: now,
+                end_time: now + 100,
+                reserve_price: Uint128::new(50),
+                min_increment: Some(Uint128::new(10)),
+                extension_window: None,
+            }),
+            0
+        )
+        .unwrap();
+
+    let bid = ExecuteMsg::PlaceBid(PlaceBid {
+        collection: collection.clone(),
+        token_id: Uint128::new(100),
+        recipient: None,
+    });
+    suite.execute("buyer1", bid.clone(), 50).unwrap();
+    let err = error(suite.execute("buyer2", bid.clone(), 55));
+    assert!(matches!(err, ContractError::BidTooLow {}));
+    suite.execute("buyer2", bid, 70).unwrap();
+    assert_eq!(suite.balance("buyer1"), BALANCE);
+
+    let settle = ExecuteMsg::SettleAuction(SettleAuction {
+        collection: collection.clone(),
+        token_id: Uint128::new(100),
+    });
+    let err = error(suite.execute("buyer1", settle.clone(), 0));
+    assert!(matches!(err, ContractError::AuctionNotEnded {}));
+
+    suite.set_time(now + 100);
+    suite.execute("buyer1", settle, 0).unwrap();
+    assert_eq!(suite.balance(CREATOR), 70);
+    assert_eq!(suite.balance("buyer2"), BALANCE - 70);
+    assert_eq!(suite.owner_of(&collection, "100").unwrap(), "buyer2");
+}
+
+#[test]
+fn supply_updates_cannot_cover_an_auctioned_id() {
+    let mut suite = Suite::new(0);
+    let now = suite.now();
+    let collection = suite.register(register_msg(5, vec![group("public", 100)]));
+
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::CreateAuction(CreateAuction {
+                collection: collection.clone(),
+                token_id: Uint128::new(10),
+                payee: None,
+                start_time: now,
+                end_time: now + 100,
+                reserve_price: Uint128::new(50),
+                min_increment: None,
+                extension_window: None,
+            }),
+            0
+        )
+        .unwrap();
+
+    let update = |supply| {
+        ExecuteMsg::UpdateCollection(update_msg(&collection, supply, vec![group("public", 100)]))
+    };
+    assert!(suite.execute(CREATOR, update(11), 0).is_err());
+    suite.execute(CREATOR, update(10), 0).unwrap();
+}
+
+fn presale_group(now: u64) -> MintGroup {
+    let mut group = group("presale", 100);
+    group.start_time = now + 10;
+    group.presale = Some(Presale {
+        start_time: now,
+        claim_on_reveal: None,
+    });
+    group
+}
+
+#[test]
+fn claim_mints_what_the_supply_allows_and_refunds_the_rest() {
+    let mut suite = Suite::new(10);
+    let now = suite.now();
+    let collection = suite.register(register_msg(5, vec![presale_group(now)]));
+
+    let reserve = ExecuteMsg::Reserve(Reserve {
+        collection: collection.clone(),
+        group: "presale".to_string(),
+        amount: Uint128::new(3),
+        merkle_proof: None,
+        merkle_proof_address_type: None,
+        evm_address_proof: None,
+        vault: None,
+    });
+    suite.execute("buyer", reserve, 330).unwrap();
+    assert_eq!(suite.balance(suite.lighthouse.as_str()), 330);
+
+    // The creator shrinks the supply below the reservation
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::UpdateCollection(update_msg(&collection, 2, vec![presale_group(now)])),
+            0
+        )
+        .unwrap();
+
+    let claim = ExecuteMsg::Claim(Claim {
+        collection: collection.clone(),
+        group: "presale".to_string(),
+    });
+    let err = error(suite.execute("buyer", claim.clone(), 0));
+    assert!(matches!(err, ContractError::GroupNotOpenToMint {}));
+
+    suite.set_time(now + 10);
+    suite.execute("buyer", claim.clone(), 0).unwrap();
+    assert_eq!(suite.balance("buyer"), BALANCE - 220);
+    assert_eq!(suite.balance(CREATOR), 200);
+    assert_eq!(suite.balance(ADMIN), 20);
+    assert_eq!(suite.balance(suite.lighthouse.as_str()), 0);
+    assert_eq!(suite.owner_of(&collection, "0").unwrap(), "buyer");
+    assert_eq!(suite.owner_of(&collection, "1").unwrap(), "buyer");
+
+    let err = error(suite.execute("buyer", claim, 0));
+    assert!(matches!(err, ContractError::NothingToClaim {}));
+}
+
+#[test]
+fn claim_refunds_in_full_when_the_presale_group_was_removed() {
+    let mut suite = Suite::new(10);
+    let now = suite.now();
+    let collection = suite.register(register_msg(5, vec![presale_group(now)]));
+
+    let reserve = ExecuteMsg::Reserve(Reserve {
+        collection: collection.clone(),
+        group: "presale".to_string(),
+        amount: Uint128::new(2),
+        merkle_proof: None,
+        merkle_proof_address_type: None,
+        evm_address_proof: None,
+        vault: None,
+    });
+    suite.execute("buyer", reserve, 220).unwrap();
+
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::UpdateCollection(update_msg(&collection, 5, vec![group("public", 100)])),
+            0
+        )
+        .unwrap();
+
+    let claim = ExecuteMsg::Claim(Claim {
+        collection,
+        group: "presale".to_string(),
+    });
+    suite.execute("buyer", claim, 0).unwrap();
+    assert_eq!(suite.balance("buyer"), BALANCE);
+    assert_eq!(suite.balance(suite.lighthouse.as_str()), 0);
+}
+
+#[test]
+fn reserve_accepts_vaults_like_mint() {
+    let mut suite = Suite::new(0);
+    let now = suite.now();
+    let mut presale = presale_group(now);
+    presale.allowlist = Some(true);
+    let collection = suite.register(register_msg(5, vec![presale]));
+
+    suite
+        .execute(
+            CREATOR,
+            ExecuteMsg::AddToAllowlist(AddToAllowlist {
+                collection: collection.clone(),
+                group: "presale".to_string(),
+                entries: vec![AllowlistEntryMsg {
+                    address: "buyer1".to_string(),
+                    allocation: Some(Uint128::one()),
+                }],
+            }),
+            0
+        )
+        .unwrap();
+    suite
+        .execute(
+            "buyer1",
+            ExecuteMsg::DelegateWallet(DelegateWallet {
+                delegate: "buyer".to_string(),
+                collection: Some(collection.clone()),
+            }),
+            0
+        )
+        .unwrap();
+
+    let reserve = |vault: Option<&str>| {
+        ExecuteMsg::Reserve(Reserve {
+            collection: collection.clone(),
+            group: "presale".to_string(),
+            amount: Uint128::one(),
+            merkle_proof: None,
+            merkle_proof_address_type: None,
+            evm_address_proof: None,
+            vault: vault.map(|v| v.to_string()),
+        })
+    };
+
+    let err = error(suite.execute("buyer", reserve(None), 100));
+    assert!(matches!(err, ContractError::NotOnAllowlist {}));
+
+    suite.execute("buyer", reserve(Some("buyer1")), 100).unwrap();
+
+    // The vault's allocation is used up, whoever reserves for it
+    let err = error(suite.execute("buyer", reserve(Some("buyer1")), 100));
+    assert!(matches!(err, ContractError::MaxTokensMinted {}));
+    let err = error(suite.execute("buyer1", reserve(None), 100));
+    assert!(matches!(err, ContractError::MaxTokensMinted {}));
+}
+
+#[test]
+fn global_delegations_can_be_revoked_once() {
+    let mut suite = Suite::new(0);
+
+    suite
+        .execute(
+            "buyer1",
+            ExecuteMsg::DelegateWallet(DelegateWallet {
+                delegate: "buyer".to_string(),
+                collection: None,
+            }),
+            0
+        )
+        .unwrap();
+
+    let revoke = ExecuteMsg::RevokeDelegation(RevokeDelegation {
+        delegate: "buyer".to_string(),
+        collection: None,
+    });
+    suite.execute("buyer1", revoke.clone(), 0).unwrap();
+
+    let err = error(suite.execute("buyer1", revoke, 0));
+    assert!(matches!(err, ContractError::NotDelegated {}));
+}